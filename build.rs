@@ -14,9 +14,11 @@ fn main() {
         #[cfg(not(feature = "clipboard-crate"))]
         println!("cargo:rustc-cfg=feature=\"clipboard-bin\"");
 
-        // xclip and xsel paths are inserted at compile time
+        // xclip, xsel, wl-copy and wl-paste paths are inserted at compile time
         println!("cargo:rerun-if-env-changed=XCLIP_PATH");
         println!("cargo:rerun-if-env-changed=XSEL_PATH");
+        println!("cargo:rerun-if-env-changed=WL_COPY_PATH");
+        println!("cargo:rerun-if-env-changed=WL_PASTE_PATH");
     }
 
     #[cfg(all(
@@ -0,0 +1,47 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use clap::ArgMatches;
+
+use crate::cmd::matcher::{clear_clipboard::ClearClipboardMatcher, main::MainMatcher, Matcher};
+use crate::error::ActionError;
+use crate::util::sha256;
+
+/// A clear clipboard action.
+pub struct ClearClipboard<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> ClearClipboard<'a> {
+    /// Construct a new clear clipboard action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the clear clipboard action.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_clear = ClearClipboardMatcher::with(self.cmd_matches).unwrap();
+
+        // Read the content hash before waiting, then clear the clipboard after the delay
+        let content_hash = matcher_clear.content_hash();
+        sleep(Duration::from_secs(matcher_clear.delay()));
+        let clipboard = matcher_main.clipboard();
+
+        // Keep content copied in the meantime, backends that can't read the clipboard such as
+        // OSC 52 are cleared regardless
+        if let Some(hash) = content_hash {
+            if let Ok(content) = clipboard.get() {
+                if !sha256(content.as_bytes()).eq_ignore_ascii_case(&hash) {
+                    return Ok(());
+                }
+            }
+        }
+
+        clipboard.clear()?;
+
+        Ok(())
+    }
+}
@@ -1,14 +1,20 @@
 use chrono::Duration;
 use clap::ArgMatches;
+use failure::Fail;
 use ffsend_api::config::SEND_DEFAULT_EXPIRE_TIME;
 use prettytable::{format::FormatBuilder, Cell, Row, Table};
 
-use crate::client::to_duration;
+use super::select_api_version;
+use crate::client::{create_config, to_duration};
 use crate::cmd::matcher::{debug::DebugMatcher, main::MainMatcher, Matcher};
+use crate::config::API_VERSION_ASSUME;
 use crate::error::ActionError;
-#[cfg(feature = "clipboard-bin")]
-use crate::util::ClipboardType;
-use crate::util::{api_version_list, features_list, format_bool, format_duration};
+use crate::limits::Limits;
+use crate::password_policy::Policy as PasswordPolicy;
+use crate::retry::Retry;
+use crate::util::{
+    api_version_list, features_list, format_bool, format_bytes, format_duration, print_error,
+};
 
 /// A file debug action.
 pub struct Debug<'a> {
@@ -78,6 +84,53 @@ impl<'a> Debug<'a> {
             ),
         ]));
 
+        // The limits of the host
+        let host = matcher_debug.host();
+        let client = create_config(&matcher_main).client(false);
+        let mut desired_version = matcher_main.api();
+        if let Err(err) = select_api_version(
+            &client,
+            host.clone(),
+            &mut desired_version,
+            &Retry::new(&matcher_main),
+        ) {
+            print_error(err.context("failed to determine host API version, ignoring"));
+        }
+        let api_version = desired_version.version().unwrap_or(API_VERSION_ASSUME);
+        let limits = Limits::for_host(&matcher_main, &client, &host, api_version, false);
+        table.add_row(Row::new(vec![
+            Cell::new("Host limits:"),
+            Cell::new(if limits.from_host {
+                "from host"
+            } else {
+                "defaults"
+            }),
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::new("Max file size:"),
+            Cell::new(&format_bytes(limits.size_max)),
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::new("Download limits:"),
+            Cell::new(&limits.downloads_list()),
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::new("Max expiry:"),
+            Cell::new(&format_duration(Duration::seconds(
+                limits.expiry_max as i64,
+            ))),
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::new("Expiry times:"),
+            Cell::new(&limits.expiry_list()),
+        ]));
+
+        // The password policy for the host
+        table.add_row(Row::new(vec![
+            Cell::new("Password policy:"),
+            Cell::new(&PasswordPolicy::for_host(&host, None).summary()),
+        ]));
+
         // The default host
         table.add_row(Row::new(vec![
             Cell::new("Default expiry:"),
@@ -108,10 +161,10 @@ impl<'a> Debug<'a> {
         ]));
 
         // Clipboard information
-        #[cfg(feature = "clipboard-bin")]
+        #[cfg(feature = "clipboard")]
         table.add_row(Row::new(vec![
             Cell::new("Clipboard:"),
-            Cell::new(&format!("{}", ClipboardType::select())),
+            Cell::new(&format!("{}", matcher_main.clipboard())),
         ]));
 
         // Show whether quiet is used
@@ -1,5 +1,9 @@
 use clap::ArgMatches;
+#[cfg(feature = "history")]
+use failure::Fail;
 use ffsend_api::action::delete::{Delete as ApiDelete, Error as DeleteError};
+#[cfg(feature = "history")]
+use ffsend_api::action::exists::Exists as ApiExists;
 use ffsend_api::file::remote_file::{FileParseError, RemoteFile};
 
 use crate::client::create_config;
@@ -7,7 +11,11 @@ use crate::cmd::matcher::{delete::DeleteMatcher, main::MainMatcher, Matcher};
 use crate::error::ActionError;
 #[cfg(feature = "history")]
 use crate::history_tool;
+#[cfg(feature = "history")]
+use crate::retry::Retry;
 use crate::util::{ensure_owner_token, print_success};
+#[cfg(feature = "history")]
+use crate::util::{print_error, prompt_yes, quit, quit_error_msg, ErrorHintsBuilder};
 
 /// A file delete action.
 pub struct Delete<'a> {
@@ -27,6 +35,14 @@ impl<'a> Delete<'a> {
         let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
         let matcher_delete = DeleteMatcher::with(self.cmd_matches).unwrap();
 
+        // Delete all files from history
+        #[cfg(feature = "history")]
+        {
+            if matcher_delete.all() || matcher_delete.recipient().is_some() {
+                return Self::invoke_all(&matcher_main, &matcher_delete);
+            }
+        }
+
         // Get the share link
         let url = matcher_delete.url();
 
@@ -38,6 +54,8 @@ impl<'a> Delete<'a> {
         let mut file = RemoteFile::parse_url(url, matcher_delete.owner())?;
         #[cfg(feature = "history")]
         history_tool::derive_file_properties(&matcher_main, &mut file);
+        #[cfg(feature = "secret-store")]
+        history_tool::derive_owner_token(&matcher_main, &mut file);
 
         // Ensure the owner token is set
         ensure_owner_token(file.owner_token_mut(), &matcher_main, false);
@@ -60,6 +78,182 @@ impl<'a> Delete<'a> {
 
         Ok(())
     }
+
+    /// Delete all files from history that have an owner token, optionally filtered.
+    ///
+    /// If a recipient is given, only the shares created for that recipient are deleted. Deleting
+    /// multiple files must be confirmed, deleting a single recipient share doesn't.
+    ///
+    /// Files may be selected by their remote state, whether they expired or are still active. A
+    /// file has expired if its expiry time passed or the host reports it doesn't exist anymore.
+    /// Expired files are only removed from history, without confirmation, so these don't need an
+    /// owner token.
+    ///
+    /// Each file is deleted separately, failures are reported per file and don't stop other files
+    /// from being deleted. The program quits with an error if any file failed to delete.
+    #[cfg(feature = "history")]
+    fn invoke_all(
+        matcher_main: &MainMatcher,
+        matcher_delete: &DeleteMatcher,
+    ) -> Result<(), ActionError> {
+        // Collect the files to delete with their label, release the history before deleting
+        let expired = matcher_delete.expired();
+        let files: Vec<(RemoteFile, String)> = {
+            let history = history_tool::load_with_owner_tokens(matcher_main);
+            history
+                .files_sorted()
+                .into_iter()
+                .filter(|file| expired || file.has_owner_token())
+                .filter(|file| match matcher_delete.recipient() {
+                    Some(recipient) => history
+                        .meta(file.id())
+                        .and_then(|meta| meta.recipient.as_ref())
+                        .map(|r| r == recipient)
+                        .unwrap_or(false),
+                    None => true,
+                })
+                .filter(|file| match matcher_delete.filter() {
+                    Some(filter) => {
+                        let meta = history.meta(file.id()).cloned().unwrap_or_default();
+                        meta.alias
+                            .iter()
+                            .chain(meta.name.iter())
+                            .any(|v| v.contains(filter))
+                            || file.download_url(false).as_str().contains(filter)
+                    }
+                    None => true,
+                })
+                .map(|file| (file.clone(), history.label(file)))
+                .collect()
+        };
+
+        // There must be files to delete
+        if files.is_empty() {
+            if let Some(recipient) = matcher_delete.recipient() {
+                quit_error_msg(
+                    if expired {
+                        format!("no shares in history for recipient '{}'", recipient)
+                    } else {
+                        format!(
+                            "no shares in history for recipient '{}' with a known owner token",
+                            recipient
+                        )
+                    },
+                    ErrorHintsBuilder::default()
+                        .history(true)
+                        .verbose(false)
+                        .build()
+                        .unwrap(),
+                );
+            }
+            if !matcher_main.quiet() {
+                eprintln!("No files in history to delete");
+            }
+            return Ok(());
+        }
+
+        // Create client
+        let client_config = create_config(matcher_main);
+        let client = client_config.client(false);
+
+        // Select files by their remote state
+        let files = if expired || matcher_delete.active() {
+            let retry = Retry::new(matcher_main);
+            let files: Vec<_> = files
+                .into_iter()
+                .filter(|(file, label)| {
+                    if file.has_expired() {
+                        return expired;
+                    }
+                    match retry.invoke("existence check", || ApiExists::new(file).invoke(&client)) {
+                        Ok(exists) => exists.exists() != expired,
+                        Err(err) => {
+                            print_error(err.context(format!(
+                                "failed to check whether '{}' exists, skipping",
+                                label
+                            )));
+                            false
+                        }
+                    }
+                })
+                .collect();
+            if files.is_empty() {
+                if !matcher_main.quiet() {
+                    eprintln!("No files in history to delete");
+                }
+                return Ok(());
+            }
+            files
+        } else {
+            files
+        };
+
+        // Expired files are gone from the remote host already, only remove them from history
+        if expired {
+            for (file, label) in &files {
+                history_tool::remove(matcher_main, file);
+                if !matcher_main.quiet() {
+                    eprintln!("Expired: {}", label);
+                }
+            }
+            print_success("Expired files removed from history");
+            return Ok(());
+        }
+
+        // Confirm deleting multiple files
+        let single = matcher_delete.recipient().is_some() && files.len() == 1;
+        if !matcher_main.force()
+            && !single
+            && !prompt_yes(
+                &format!("Delete {} file(s) from the remote host?", files.len()),
+                None,
+                matcher_main,
+            )
+        {
+            eprintln!("Delete cancelled");
+            quit();
+        }
+
+        // Delete each file, report the result
+        let mut failed = 0;
+        for (file, label) in &files {
+            match ApiDelete::new(file, None).invoke(&client) {
+                Ok(()) => {
+                    history_tool::remove(matcher_main, file);
+                    if !matcher_main.quiet() {
+                        eprintln!("Deleted: {}", label);
+                    }
+                }
+                Err(DeleteError::Expired) => {
+                    history_tool::remove(matcher_main, file);
+                    if !matcher_main.quiet() {
+                        eprintln!("Expired: {}", label);
+                    }
+                }
+                Err(err) => {
+                    failed += 1;
+                    print_error(err.context(format!("failed to delete '{}'", label)));
+                }
+            }
+        }
+
+        // Quit with an error if any file failed to delete
+        if failed > 0 {
+            quit_error_msg(
+                format!("failed to delete {} of {} files", failed, files.len()),
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            );
+        }
+
+        // Print a success message
+        print_success(if files.len() == 1 {
+            "File deleted"
+        } else {
+            "Files deleted"
+        });
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Fail)]
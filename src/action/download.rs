@@ -1,20 +1,24 @@
 use std::env::current_dir;
-use std::fs::create_dir_all;
-#[cfg(feature = "archive")]
+use std::fs::{self, create_dir_all, remove_file};
 use std::io::Error as IoError;
-use std::path::{self, PathBuf};
+use std::path::{self, Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use clap::ArgMatches;
 use failure::Fail;
 use ffsend_api::action::download::{Download as ApiDownload, Error as DownloadError};
 use ffsend_api::action::exists::{Error as ExistsError, Exists as ApiExists};
-use ffsend_api::action::metadata::{Error as MetadataError, Metadata as ApiMetadata};
+use ffsend_api::action::metadata::{
+    Error as MetadataError, Metadata as ApiMetadata, MetadataResponse,
+};
 use ffsend_api::action::version::Error as VersionError;
+use ffsend_api::api::Version;
+use ffsend_api::client::Client;
 use ffsend_api::file::remote_file::{FileParseError, RemoteFile};
 use ffsend_api::pipe::ProgressReporter;
+use tempfile::Builder as TempBuilder;
 #[cfg(feature = "archive")]
-use tempfile::{Builder as TempBuilder, NamedTempFile};
+use tempfile::NamedTempFile;
 
 use super::select_api_version;
 #[cfg(feature = "archive")]
@@ -23,10 +27,13 @@ use crate::client::create_config;
 use crate::cmd::matcher::{download::DownloadMatcher, main::MainMatcher, Matcher};
 #[cfg(feature = "history")]
 use crate::history_tool;
+use crate::host::parse_host;
+use crate::manifest::{self, Manifest};
 use crate::progress::ProgressBar;
+use crate::retry::Retry;
 use crate::util::{
-    ensure_enough_space, ensure_password, follow_url, print_error, prompt_yes, quit, quit_error,
-    quit_error_msg, ErrorHints,
+    ensure_enough_space, ensure_password, follow_url, highlight, print_error, prompt_yes, quit,
+    quit_error, quit_error_msg, sha256_file, ErrorHints, ErrorHintsBuilder,
 };
 
 /// A file download action.
@@ -50,10 +57,11 @@ impl<'a> Download<'a> {
         // Create a regular client
         let client_config = create_config(&matcher_main);
         let client = client_config.clone().client(false);
+        let retry = Retry::new(&matcher_main);
 
         // Get the share URL, attempt to follow it
         let url = matcher_download.url();
-        let url = match follow_url(&client, &url) {
+        let mut url = match follow_url(&client, &url) {
             Ok(url) => url,
             Err(err) => {
                 print_error(err.context("failed to follow share URL, ignoring").compat());
@@ -61,27 +69,46 @@ impl<'a> Download<'a> {
             }
         };
 
+        // Use the separately specified secret key
+        if let Some(key) = matcher_download.key() {
+            url.set_fragment(Some(key));
+        }
+
         // Guess the host
         let host = matcher_download.guess_host(Some(url.clone()));
 
         // Determine the API version to use
         let mut desired_version = matcher_main.api();
-        select_api_version(&client, host, &mut desired_version)?;
+        select_api_version(&client, host, &mut desired_version, &retry)?;
         let api_version = desired_version.version().unwrap();
 
         // Parse the remote file based on the share URL
         let file = RemoteFile::parse_url(url, None)?;
+        if file.secret_raw().is_empty() {
+            quit_error_msg(
+                "the share URL does not contain the secret key",
+                ErrorHintsBuilder::default()
+                    .add_info(format!(
+                        "Specify the secret key using '{}'",
+                        highlight("--key KEY")
+                    ))
+                    .force(false)
+                    .verbose(false)
+                    .build()
+                    .unwrap(),
+            );
+        }
 
         // Get the target file or directory, and the password
         let target = matcher_download.output();
         let mut password = matcher_download.password();
 
         // Check whether the file exists
-        let exists = ApiExists::new(&file).invoke(&client)?;
+        let exists = retry.invoke("existence check", || ApiExists::new(&file).invoke(&client))?;
         if !exists.exists() {
             // Remove the file from the history manager if it does not exist
             #[cfg(feature = "history")]
-            history_tool::remove(&matcher_main, &file);
+            history_tool::remove_keep_token(&matcher_main, &file);
 
             return Err(Error::Expired);
         }
@@ -95,7 +122,22 @@ impl<'a> Download<'a> {
         );
 
         // Fetch the file metadata
-        let metadata = ApiMetadata::new(&file, password.clone(), false).invoke(&client)?;
+        let metadata = retry.invoke("metadata request", || {
+            ApiMetadata::new(&file, password.clone(), false).invoke(&client)
+        })?;
+
+        // Download and reassemble files split across multiple shares
+        if Manifest::is_candidate(metadata.metadata().name(), metadata.size()) {
+            return Self::download_split(
+                &matcher_main,
+                &matcher_download,
+                client_config.client(true),
+                api_version,
+                &file,
+                password,
+                metadata,
+            );
+        }
 
         // A temporary archive file, only used when archiving
         // The temporary file is stored here, to ensure it's lifetime exceeds the upload process
@@ -168,14 +210,34 @@ impl<'a> Download<'a> {
         // Create a transfer client
         let transfer_client = client_config.client(true);
 
+        // Remember the file name to reference the file by in history
+        #[cfg(feature = "history")]
+        let history_name = metadata.metadata().name().to_owned();
+
         // Execute an download action
         let progress = if !matcher_main.quiet() {
             Some(progress_reader)
         } else {
             None
         };
-        ApiDownload::new(api_version, &file, target, password, false, Some(metadata))
-            .invoke(&transfer_client, progress)?;
+        // The metadata nonce is used up after the first attempt, fetch it again when retrying
+        let mut metadata = Some(metadata);
+        retry.invoke("download", || {
+            ApiDownload::new(
+                api_version,
+                &file,
+                target.clone(),
+                password.clone(),
+                false,
+                metadata.take(),
+            )
+            .invoke(&transfer_client, progress.clone())
+        })?;
+
+        // Verify the checksum of the downloaded file, delete it on mismatch
+        if let Some(expected) = matcher_download.expect_sha256() {
+            Self::verify_checksum(&target, expected)?;
+        }
 
         // Extract the downloaded file if working with an archive
         #[cfg(feature = "archive")]
@@ -192,7 +254,7 @@ impl<'a> Download<'a> {
 
         // Add the file to the history
         #[cfg(feature = "history")]
-        history_tool::add(&matcher_main, file, true);
+        history_tool::add_named(&matcher_main, file, Some(history_name), true);
 
         // TODO: open the file, or it's location
         // TODO: copy the file location
@@ -200,6 +262,148 @@ impl<'a> Download<'a> {
         Ok(())
     }
 
+    /// Download a file split across multiple shares, described by the given manifest share.
+    ///
+    /// All parts are downloaded and verified, and reassembled into the output file.
+    fn download_split(
+        matcher_main: &MainMatcher,
+        matcher_download: &DownloadMatcher,
+        client: Client,
+        api_version: Version,
+        file: &RemoteFile,
+        password: Option<String>,
+        metadata: MetadataResponse,
+    ) -> Result<(), Error> {
+        // Allocate a temporary directory to download the manifest to
+        let tmp_dir = TempBuilder::new()
+            .prefix(&format!(".{}-manifest-", crate_name!()))
+            .tempdir()
+            .map_err(SplitError::TempDir)?;
+
+        // Download the manifest
+        let retry = Retry::new(matcher_main);
+        let manifest_path = tmp_dir.path().join("manifest");
+        let name = metadata.metadata().name().to_owned();
+        let mut metadata = Some(metadata);
+        retry.invoke("manifest download", || {
+            ApiDownload::new(
+                api_version,
+                file,
+                manifest_path.clone(),
+                password.clone(),
+                false,
+                metadata.take(),
+            )
+            .invoke(&client, None)
+        })?;
+
+        // Load the manifest, keep the share as regular file if it isn't a manifest after all
+        let manifest = match Manifest::load(&manifest_path) {
+            Ok(manifest) => manifest,
+            Err(manifest::Error::NotManifest) => {
+                let target =
+                    Self::prepare_path(&matcher_download.output(), &name, matcher_main, true);
+                fs::copy(&manifest_path, &target).map_err(SplitError::Copy)?;
+                if let Some(expected) = matcher_download.expect_sha256() {
+                    Self::verify_checksum(&target, expected)?;
+                }
+
+                #[cfg(feature = "history")]
+                history_tool::add_named(matcher_main, file.clone(), Some(name), true);
+
+                return Ok(());
+            }
+            Err(err) => return Err(SplitError::Manifest(err).into()),
+        };
+
+        // Prepare the output path, ensure there is enough disk space for the parts and output
+        let target = Self::prepare_path(
+            &matcher_download.output(),
+            &manifest.name,
+            matcher_main,
+            true,
+        );
+        let target_dir = target.parent().unwrap();
+        if !matcher_main.force() {
+            ensure_enough_space(target_dir, manifest.size * 2);
+        }
+
+        // Allocate a temporary directory next to the output file to download the parts to
+        let tmp_dir = TempBuilder::new()
+            .prefix(&format!(".{}-split-", crate_name!()))
+            .tempdir_in(target_dir)
+            .map_err(SplitError::TempDir)?;
+
+        // Create a progress bar reporter
+        let progress_bar = Arc::new(Mutex::new(ProgressBar::new_download()));
+        let progress_reader: Arc<Mutex<dyn ProgressReporter>> = progress_bar;
+
+        // Download and verify each part
+        let mut parts = Vec::with_capacity(manifest.parts.len());
+        for (i, part) in manifest.parts.iter().enumerate() {
+            if !matcher_main.quiet() {
+                eprintln!("Downloading part {} of {}...", i + 1, manifest.parts.len());
+            }
+
+            let part_url = parse_host(&part.url).map_err(|_| SplitError::PartUrl(i + 1))?;
+            let part_file = RemoteFile::parse_url(part_url, None)?;
+            let part_path = tmp_dir.path().join(format!("part{}", i + 1));
+            retry.invoke("part download", || {
+                let progress = if !matcher_main.quiet() {
+                    Some(progress_reader.clone())
+                } else {
+                    None
+                };
+                ApiDownload::new(
+                    api_version,
+                    &part_file,
+                    part_path.clone(),
+                    password.clone(),
+                    true,
+                    None,
+                )
+                .invoke(&client, progress)
+            })?;
+
+            // Verify the part integrity
+            if sha256_file(&part_path).map_err(Error::Checksum)? != part.sha256 {
+                return Err(SplitError::PartChecksum(i + 1).into());
+            }
+            parts.push(part_path);
+        }
+
+        // Reassemble the file, verify the result
+        manifest::join(&parts, &target).map_err(SplitError::Join)?;
+        Self::verify_checksum(&target, manifest.sha256.to_lowercase())?;
+        if let Some(expected) = matcher_download.expect_sha256() {
+            Self::verify_checksum(&target, expected)?;
+        }
+
+        // Add the file to the history
+        #[cfg(feature = "history")]
+        history_tool::add_named(matcher_main, file.clone(), Some(manifest.name), true);
+
+        Ok(())
+    }
+
+    /// Verify the SHA-256 checksum of the downloaded file at the given path.
+    ///
+    /// If the checksum doesn't match the `expected` checksum, the file is deleted and an error is
+    /// returned.
+    fn verify_checksum(path: &Path, expected: String) -> Result<(), Error> {
+        let actual = sha256_file(path).map_err(Error::Checksum)?;
+        if actual != expected {
+            if let Err(err) = remove_file(path) {
+                print_error(
+                    err.context("failed to delete downloaded file with invalid checksum")
+                        .compat(),
+                );
+            }
+            return Err(Error::ChecksumMismatch { expected, actual });
+        }
+        Ok(())
+    }
+
     /// This methods prepares a full file path to use for the file to
     /// download, based on the current directory, the original file name,
     /// and the user input.
@@ -378,6 +582,21 @@ pub enum Error {
     #[fail(display = "failed the extraction procedure")]
     Extract(#[cause] ExtractError),
 
+    /// An error occurred while downloading a file split across multiple shares.
+    #[fail(display = "failed to download split file")]
+    Split(#[cause] SplitError),
+
+    /// Failed to compute the checksum of the downloaded file.
+    #[fail(display = "failed to compute checksum of downloaded file")]
+    Checksum(#[cause] IoError),
+
+    /// The checksum of the downloaded file did not match the expected checksum.
+    #[fail(
+        display = "checksum mismatch, deleted the downloaded file (expected SHA-256 {}, got {})",
+        expected, actual
+    )]
+    ChecksumMismatch { expected: String, actual: String },
+
     /// The given Send file has expired, or did never exist in the first place.
     #[fail(display = "the file has expired or did never exist")]
     Expired,
@@ -407,6 +626,12 @@ impl From<MetadataError> for Error {
     }
 }
 
+impl From<SplitError> for Error {
+    fn from(err: SplitError) -> Error {
+        Error::Split(err)
+    }
+}
+
 impl From<DownloadError> for Error {
     fn from(err: DownloadError) -> Error {
         Error::Download(err)
@@ -431,3 +656,30 @@ pub enum ExtractError {
     #[fail(display = "failed to extract archive contents to target directory")]
     Extract(#[cause] IoError),
 }
+
+#[derive(Debug, Fail)]
+pub enum SplitError {
+    /// An error occurred while creating the temporary directory for parts.
+    #[fail(display = "failed to create temporary directory for parts")]
+    TempDir(#[cause] IoError),
+
+    /// Failed to copy a share that isn't a manifest to the output file.
+    #[fail(display = "failed to copy downloaded file to output")]
+    Copy(#[cause] IoError),
+
+    /// Failed to load the downloaded manifest.
+    #[fail(display = "failed to load manifest")]
+    Manifest(#[cause] manifest::Error),
+
+    /// The manifest contains an invalid share URL for a part.
+    #[fail(display = "manifest contains invalid share URL for part {}", _0)]
+    PartUrl(usize),
+
+    /// The checksum of a downloaded part did not match.
+    #[fail(display = "checksum mismatch for part {}", _0)]
+    PartChecksum(usize),
+
+    /// Failed to reassemble the parts into the output file.
+    #[fail(display = "failed to reassemble parts")]
+    Join(#[cause] manifest::Error),
+}
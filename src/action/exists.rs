@@ -8,6 +8,7 @@ use crate::cmd::matcher::{exists::ExistsMatcher, Matcher};
 use crate::error::ActionError;
 #[cfg(feature = "history")]
 use crate::history_tool;
+use crate::retry::Retry;
 
 /// A file exists action.
 pub struct Exists<'a> {
@@ -33,12 +34,14 @@ impl<'a> Exists<'a> {
         // Create a reqwest client
         let client_config = create_config(&matcher_main);
         let client = client_config.client(false);
+        let retry = Retry::new(&matcher_main);
 
         // Parse the remote file based on the share URL
         let file = RemoteFile::parse_url(url, None)?;
 
         // Make sure the file exists
-        let exists_response = ApiExists::new(&file).invoke(&client)?;
+        let exists_response =
+            retry.invoke("existence check", || ApiExists::new(&file).invoke(&client))?;
         let exists = exists_response.exists();
 
         // Print the results
@@ -53,7 +56,7 @@ impl<'a> Exists<'a> {
             if exists {
                 history_tool::add(&matcher_main, file, false);
             } else {
-                history_tool::remove(&matcher_main, &file);
+                history_tool::remove_keep_token(&matcher_main, &file);
             }
         }
 
@@ -28,7 +28,7 @@ impl<'a> Completions<'a> {
         let shells = matcher_completions.shells();
         let dir = matcher_completions.output();
         let quiet = matcher_main.quiet();
-        let mut app = crate::cmd::handler::Handler::build();
+        let mut app = crate::cmd::handler::Handler::build_completions();
 
         // If the directory does not exist yet, attempt to create it
         if !dir.is_dir() {
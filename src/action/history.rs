@@ -1,10 +1,23 @@
+use chrono::Utc;
 use clap::ArgMatches;
 use failure::Fail;
+use ffsend_api::file::remote_file::RemoteFile;
 use prettytable::{format::FormatBuilder, Cell, Row, Table};
 
 use crate::cmd::matcher::{history::HistoryMatcher, main::MainMatcher, Matcher};
 use crate::error::ActionError;
-use crate::history::{History as HistoryManager, LoadError as HistoryLoadError};
+#[cfg(feature = "clipboard")]
+use crate::history::FileMeta;
+use crate::history::{FindError, History as HistoryManager, LoadError as HistoryLoadError};
+#[cfg(feature = "secret-store")]
+use crate::history_tool;
+use crate::host::parse_host;
+#[cfg(all(feature = "clipboard", feature = "secret-store"))]
+use crate::message::CopyMode;
+#[cfg(feature = "clipboard")]
+use crate::message::ShareInfo;
+#[cfg(feature = "clipboard")]
+use crate::util::set_clipboard;
 use crate::util::{format_duration, quit_error, quit_error_msg, ErrorHintsBuilder};
 
 /// A history action.
@@ -47,6 +60,8 @@ impl<'a> History<'a> {
 
         // Clear all history
         if matcher_history.clear() {
+            #[cfg(feature = "secret-store")]
+            let files = history.files().clone();
             history.clear();
 
             // Save history
@@ -57,12 +72,19 @@ impl<'a> History<'a> {
                 );
             }
 
+            // Forget the owner tokens in the secret store
+            #[cfg(feature = "secret-store")]
+            history_tool::forget_owner_tokens(&matcher_main, &files);
+
             eprintln!("History cleared");
             return Ok(());
         }
 
         // Remove history item
         if let Some(url) = matcher_history.rm() {
+            #[cfg(feature = "secret-store")]
+            let file = RemoteFile::parse_url(url.clone(), None).ok();
+
             // Remove item, print error if no item with URL was found
             match history.remove_url(url) {
                 Ok(removed) if !removed => quit_error_msg(
@@ -84,18 +106,166 @@ impl<'a> History<'a> {
                 );
             }
 
+            // Forget the owner token in the secret store
+            #[cfg(feature = "secret-store")]
+            {
+                if let Some(file) = file {
+                    history_tool::forget_owner_tokens(&matcher_main, &[file]);
+                }
+            }
+
             eprintln!("Item removed from history");
             return Ok(());
         }
 
-        // Get the list of files, and sort the first expiring files to be last
-        let mut files = history.files().clone();
-        files.sort_by(|a, b| b.expire_at().cmp(&a.expire_at()));
+        // Set an alias for a history item
+        if let Some((entry, alias)) = matcher_history.alias() {
+            // Find the file ID for the given entry
+            let id = match Self::find_id(&history, entry) {
+                Ok(id) => id,
+                Err(err) => quit_error(
+                    err.context("could not set alias for history item"),
+                    ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                ),
+            };
+
+            // Set the alias, save history
+            history.set_alias(&id, alias.into());
+            if let Err(err) = history.save() {
+                quit_error(
+                    err,
+                    ErrorHintsBuilder::default().verbose(true).build().unwrap(),
+                );
+            }
+
+            eprintln!("Alias set");
+            return Ok(());
+        }
+
+        // Copy a property of a history item to the clipboard
+        #[cfg(feature = "clipboard")]
+        {
+            if let Some(entry) = matcher_history.copy() {
+                let copy_mode = matcher_history.copy_mode();
+
+                // Owner tokens may be kept in the secret store
+                #[cfg(feature = "secret-store")]
+                {
+                    if let CopyMode::Owner | CopyMode::Message(_) = copy_mode {
+                        history_tool::fill_owner_tokens(&matcher_main, &mut history);
+                    }
+                }
+
+                // Find the file for the given entry
+                let file = match Self::find_id(&history, entry)
+                    .map(|id| history.files().iter().find(|f| f.id() == id))
+                {
+                    Ok(Some(file)) => file,
+                    Ok(None) => unreachable!(),
+                    Err(err) => quit_error(
+                        err.context("could not copy history item"),
+                        ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                    ),
+                };
+
+                // Build the content to copy from the known file properties
+                let meta = history.meta(file.id()).cloned().unwrap_or_default();
+                let FileMeta {
+                    name, recipient, ..
+                } = meta;
+                let info = ShareInfo {
+                    url: file.download_url(true),
+                    name,
+                    size: None,
+                    expiry: Some(file.expire_duration()),
+                    downloads: None,
+                    password: None,
+                    owner: file.owner_token().cloned(),
+                    recipient,
+                };
+                let content = match copy_mode.build(&info, info.url.as_str(), None) {
+                    Some(content) => content,
+                    None => quit_error_msg(
+                        format!(
+                            "could not copy history item, its {} is unknown",
+                            copy_mode.describe(),
+                        ),
+                        ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                    ),
+                };
+
+                if let Err(err) = set_clipboard(content, &matcher_main) {
+                    quit_error(
+                        err.context("failed to copy to the clipboard"),
+                        ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                    );
+                }
+
+                if !matcher_main.quiet() {
+                    eprintln!("Copied {} to clipboard", copy_mode.describe());
+                }
+                return Ok(());
+            }
+        }
+
+        // Show owner tokens from the secret store in verbose mode
+        #[cfg(feature = "secret-store")]
+        {
+            if matcher_main.verbose() {
+                history_tool::fill_owner_tokens(&matcher_main, &mut history);
+            }
+        }
+
+        // Get the list of files, the first expiring files are last
+        let files = history.files_sorted();
+
+        // Determine whether to show file names and aliases
+        let show_name = files
+            .iter()
+            .any(|f| history.meta(f.id()).and_then(|m| m.name.as_ref()).is_some());
+        let show_alias = files.iter().any(|f| {
+            history
+                .meta(f.id())
+                .and_then(|m| m.alias.as_ref())
+                .is_some()
+        });
+        let show_recipient = files.iter().any(|f| {
+            history
+                .meta(f.id())
+                .and_then(|m| m.recipient.as_ref())
+                .is_some()
+        });
+        let show_delete_at = files
+            .iter()
+            .any(|f| history.meta(f.id()).and_then(|m| m.delete_at).is_some());
+        let show_sha256 = matcher_main.verbose()
+            && files.iter().any(|f| {
+                history
+                    .meta(f.id())
+                    .and_then(|m| m.sha256.as_ref())
+                    .is_some()
+            });
 
         // Log a history table, or just the URLs in quiet mode
         if !matcher_main.quiet() {
             // Build the list of column names
-            let mut columns = vec!["#", "LINK", "EXPIRE"];
+            let mut columns = vec!["#"];
+            if show_alias {
+                columns.push("ALIAS");
+            }
+            if show_name {
+                columns.push("NAME");
+            }
+            if show_recipient {
+                columns.push("RECIPIENT");
+            }
+            columns.extend(&["LINK", "EXPIRE"]);
+            if show_delete_at {
+                columns.push("DELETE");
+            }
+            if show_sha256 {
+                columns.push("SHA-256");
+            }
             if matcher_main.verbose() {
                 columns.push("OWNER TOKEN");
             }
@@ -120,8 +290,28 @@ impl<'a> History<'a> {
                 };
 
                 // Define the cell values
-                let mut cells: Vec<String> =
-                    vec![format!("{}", i + 1), file.download_url(true).into(), expiry];
+                let meta = history.meta(file.id());
+                let mut cells: Vec<String> = vec![format!("{}", i + 1)];
+                if show_alias {
+                    cells.push(meta.and_then(|m| m.alias.clone()).unwrap_or_default());
+                }
+                if show_name {
+                    cells.push(meta.and_then(|m| m.name.clone()).unwrap_or_default());
+                }
+                if show_recipient {
+                    cells.push(meta.and_then(|m| m.recipient.clone()).unwrap_or_default());
+                }
+                cells.extend(vec![file.download_url(true).into(), expiry]);
+                if show_delete_at {
+                    cells.push(
+                        meta.and_then(|m| m.delete_at)
+                            .map(|t| format_duration(t.signed_duration_since(Utc::now())))
+                            .unwrap_or_default(),
+                    );
+                }
+                if show_sha256 {
+                    cells.push(meta.and_then(|m| m.sha256.clone()).unwrap_or_default());
+                }
                 if matcher_main.verbose() {
                     cells.push(owner_token);
                 }
@@ -140,6 +330,21 @@ impl<'a> History<'a> {
 
         Ok(())
     }
+
+    /// Find the ID of a file in the history, by a share URL or by a reference as described in
+    /// `HistoryManager::find`.
+    fn find_id(history: &HistoryManager, entry: &str) -> Result<String, FindError> {
+        // Find by share URL
+        if entry.contains("://") {
+            return parse_host(entry)
+                .ok()
+                .and_then(|url| RemoteFile::parse_url(url, None).ok())
+                .and_then(|file| history.get_file(&file).map(|f| f.id().into()))
+                .ok_or_else(|| FindError::NotFound(entry.into()));
+        }
+
+        history.find(entry).map(|f| f.id().into())
+    }
 }
 
 #[derive(Debug, Fail)]
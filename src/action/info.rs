@@ -1,19 +1,29 @@
 use chrono::Duration;
+#[cfg(feature = "history")]
+use chrono::{Local, Utc};
 use clap::ArgMatches;
 use failure::Fail;
 use ffsend_api::action::exists::{Error as ExistsError, Exists as ApiExists};
 use ffsend_api::action::info::{Error as InfoError, Info as ApiInfo};
 use ffsend_api::action::metadata::Metadata as ApiMetadata;
+use ffsend_api::api::DesiredVersion;
 use ffsend_api::file::remote_file::{FileParseError, RemoteFile};
 use prettytable::{format::FormatBuilder, Cell, Row, Table};
 
+use super::select_api_version;
 use crate::client::create_config;
 use crate::cmd::matcher::{info::InfoMatcher, main::MainMatcher, Matcher};
+use crate::config::API_VERSION_ASSUME;
 #[cfg(feature = "history")]
 use crate::history_tool;
+use crate::message::ShareInfo;
+use crate::retry::Retry;
 use crate::util::{
     ensure_owner_token, ensure_password, format_bytes, format_duration, print_error,
+    quit_error_msg, ErrorHintsBuilder,
 };
+#[cfg(feature = "clipboard")]
+use crate::util::{print_error_msg, set_clipboard};
 
 /// A file info action.
 pub struct Info<'a> {
@@ -39,17 +49,20 @@ impl<'a> Info<'a> {
         // Create a reqwest client
         let client_config = create_config(&matcher_main);
         let client = client_config.client(false);
+        let retry = Retry::new(&matcher_main);
 
         // Parse the remote file based on the share URL, derive the owner token from history
         let mut file = RemoteFile::parse_url(url, matcher_info.owner())?;
         #[cfg(feature = "history")]
         history_tool::derive_file_properties(&matcher_main, &mut file);
+        #[cfg(feature = "secret-store")]
+        history_tool::derive_owner_token(&matcher_main, &mut file);
 
         // Ask the user to set the owner token for more detailed information
         let has_owner = ensure_owner_token(file.owner_token_mut(), &matcher_main, true);
 
         // Check whether the file exists
-        let exists = ApiExists::new(&file).invoke(&client)?;
+        let exists = retry.invoke("existence check", || ApiExists::new(&file).invoke(&client))?;
         if !exists.exists() {
             // Remove the file from the history manager if it doesn't exist
             #[cfg(feature = "history")]
@@ -58,6 +71,14 @@ impl<'a> Info<'a> {
             return Err(Error::Expired);
         }
 
+        // Determine the API version used by the host, info is still useful if this fails
+        let mut desired_version = matcher_main.api();
+        if let Err(err) = select_api_version(&client, file.host(), &mut desired_version, &retry) {
+            print_error(err.context("failed to determine host API version, ignoring"));
+        }
+        let api_version_assumed = !matches!(desired_version, DesiredVersion::Use(_));
+        let api_version = desired_version.version().unwrap_or(API_VERSION_ASSUME);
+
         // Get the password, ensure the password is set when required
         let mut password = matcher_info.password();
         let has_password = ensure_password(
@@ -69,13 +90,15 @@ impl<'a> Info<'a> {
 
         // Fetch both file info and metadata
         let info = if has_owner {
-            Some(ApiInfo::new(&file, None).invoke(&client)?)
+            Some(retry.invoke("info request", || ApiInfo::new(&file, None).invoke(&client))?)
         } else {
             None
         };
         let metadata = if has_password {
-            ApiMetadata::new(&file, password, false)
-                .invoke(&client)
+            retry
+                .invoke("metadata request", || {
+                    ApiMetadata::new(&file, password.clone(), false).invoke(&client)
+                })
                 .map_err(|err| {
                     print_error(err.context("failed to fetch file metadata, showing limited info"))
                 })
@@ -93,73 +116,308 @@ impl<'a> Info<'a> {
 
         // Add the file to the history
         #[cfg(feature = "history")]
-        history_tool::add(&matcher_main, file.clone(), true);
+        history_tool::add_named(
+            &matcher_main,
+            file.clone(),
+            metadata.as_ref().map(|m| m.metadata().name().to_owned()),
+            true,
+        );
 
-        // Create a new table for the information
-        let mut table = Table::new();
-        table.set_format(FormatBuilder::new().padding(0, 2).build());
+        // Fetch the local history entry for the file
+        #[cfg(feature = "history")]
+        let entry = history_tool::lookup(&matcher_main, &file);
 
-        // Add the ID
-        table.add_row(Row::new(vec![Cell::new("ID:"), Cell::new(file.id())]));
+        // Build the report, merging all known information
+        let mut report = Report::default();
+        report.add("id", "ID:", file.id().into(), None);
+        report.add("url", "URL:", file.download_url(false).into(), None);
+        report.add("host", "Host:", file.host().into(), None);
+        if api_version_assumed {
+            report.add(
+                "api-version",
+                "API version:",
+                format!("v{} (assumed)", api_version),
+                Some(String::new()),
+            );
+        } else {
+            report.add(
+                "api-version",
+                "API version:",
+                format!("v{}", api_version),
+                Some(api_version.to_string()),
+            );
+        }
+        report.add_bool("password", "Password:", exists.requires_password());
+        report.add_bool("owner-token", "Owner token:", has_owner);
+
+        // Add the alias and upload time from history if known
+        #[cfg(feature = "history")]
+        {
+            if let Some(alias) = entry
+                .as_ref()
+                .and_then(|(_, meta)| meta.as_ref())
+                .and_then(|meta| meta.alias.clone())
+            {
+                report.add("alias", "Alias:", alias, None);
+            }
+            if let Some(sha256) = entry
+                .as_ref()
+                .and_then(|(_, meta)| meta.as_ref())
+                .and_then(|meta| meta.sha256.clone())
+            {
+                report.add("sha256", "SHA-256:", sha256, None);
+            }
+            if let Some(upload_at) = entry
+                .as_ref()
+                .and_then(|(entry, _)| history_tool::upload_at(entry))
+            {
+                let age = Utc::now().signed_duration_since(upload_at);
+                report.add(
+                    "uploaded",
+                    "Uploaded:",
+                    format!(
+                        "{} ({} ago)",
+                        upload_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+                        format_duration(age),
+                    ),
+                    Some(upload_at.to_rfc3339()),
+                );
+            }
+            if let Some(delete_at) = entry
+                .as_ref()
+                .and_then(|(_, meta)| meta.as_ref())
+                .and_then(|meta| meta.delete_at)
+            {
+                let left = delete_at.signed_duration_since(Utc::now());
+                report.add(
+                    "delete-at",
+                    "Delete at:",
+                    format!(
+                        "{} ({})",
+                        delete_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+                        if left > Duration::zero() {
+                            format!("in {}", format_duration(left))
+                        } else {
+                            "due".into()
+                        },
+                    ),
+                    Some(delete_at.to_rfc3339()),
+                );
+            }
+        }
 
         // Show file metadata if available
         if let Some(metadata) = &metadata {
             // The file name
-            table.add_row(Row::new(vec![
-                Cell::new("Name:"),
-                Cell::new(metadata.metadata().name()),
-            ]));
+            report.add("name", "Name:", metadata.metadata().name().into(), None);
 
             // The file size
             let size = metadata.size();
-            table.add_row(Row::new(vec![
-                Cell::new("Size:"),
-                Cell::new(&if size >= 1024 {
+            report.add(
+                "size",
+                "Size:",
+                if size >= 1024 {
                     format!("{} ({} B)", format_bytes(size), size)
                 } else {
                     format_bytes(size)
-                }),
-            ]));
+                },
+                Some(size.to_string()),
+            );
 
             // The file MIME
-            table.add_row(Row::new(vec![
-                Cell::new("MIME:"),
-                Cell::new(metadata.metadata().mime()),
-            ]));
+            report.add("mime", "MIME:", metadata.metadata().mime().into(), None);
         }
 
         // Show file info if available
         if let Some(info) = &info {
             // The download count
-            table.add_row(Row::new(vec![
-                Cell::new("Downloads:"),
-                Cell::new(&format!(
-                    "{} of {}",
-                    info.download_count(),
-                    info.download_limit()
-                )),
-            ]));
+            report.add(
+                "downloads",
+                "Downloads:",
+                format!("{} of {}", info.download_count(), info.download_limit()),
+                Some(info.download_count().to_string()),
+            );
+            report.add_hidden("download-limit", info.download_limit().to_string());
 
             // The time to live
             let ttl_millis = info.ttl_millis() as i64;
             let ttl = Duration::milliseconds(ttl_millis);
-            table.add_row(Row::new(vec![
-                Cell::new("Expiry:"),
-                Cell::new(&if ttl_millis >= 60 * 1000 {
+            report.add(
+                "expiry",
+                "Expiry:",
+                if ttl_millis >= 60 * 1000 {
                     format!("{} ({}s)", format_duration(&ttl), ttl.num_seconds())
                 } else {
                     format_duration(&ttl)
-                }),
-            ]));
+                },
+                Some(ttl.num_seconds().to_string()),
+            );
         }
 
-        // Print the info table
-        table.printstd();
+        // Collect the known file properties to render a share message or copy with
+        let share = ShareInfo {
+            url: file.download_url(true),
+            name: metadata.as_ref().map(|m| m.metadata().name().into()),
+            size: metadata.as_ref().map(|m| m.size()),
+            expiry: info
+                .as_ref()
+                .map(|info| Duration::milliseconds(info.ttl_millis() as i64)),
+            downloads: info.as_ref().map(|info| info.download_limit()),
+            password,
+            owner: file.owner_token().cloned(),
+            #[cfg(feature = "history")]
+            recipient: entry
+                .as_ref()
+                .and_then(|(_, meta)| meta.as_ref())
+                .and_then(|meta| meta.recipient.clone()),
+            #[cfg(not(feature = "history"))]
+            recipient: None,
+        };
+
+        // Copy the selected property or share message to the user's clipboard
+        #[cfg(feature = "clipboard")]
+        {
+            if let Some(copy_mode) = matcher_info.copy() {
+                match copy_mode.build(&share, share.url.as_str(), None) {
+                    Some(content) => {
+                        if let Err(err) = set_clipboard(content, &matcher_main) {
+                            print_error(err.context(format!(
+                                "failed to copy the {} to the clipboard, ignoring",
+                                copy_mode.describe(),
+                            )));
+                        }
+                    }
+                    None => print_error_msg(format!(
+                        "no {} to copy to the clipboard, ignoring",
+                        copy_mode.describe(),
+                    )),
+                }
+            }
+        }
+
+        // Print a share message, a single field if requested, or the full report
+        if matcher_info.print_message() {
+            if let Some(template) = matcher_info.format_message() {
+                println!("{}", share.render(&template));
+                return Ok(());
+            }
+        }
+        match matcher_info.field().as_deref() {
+            Some(field) => match report.value(field) {
+                Some(value) => println!("{}", value),
+                None => quit_error_msg(
+                    format!("the '{}' field is not available for this file", field),
+                    ErrorHintsBuilder::default()
+                        .password(metadata.is_none() && ["name", "size", "mime"].contains(&field))
+                        .owner(
+                            info.is_none()
+                                && ["downloads", "download-limit", "expiry"].contains(&field),
+                        )
+                        .verbose(false)
+                        .build()
+                        .unwrap(),
+                ),
+            },
+            None => report.print(),
+        }
 
         Ok(())
     }
 }
 
+/// Fields in the info report, which may be selected using `--field`.
+pub const FIELDS: &[&str] = &[
+    "id",
+    "url",
+    "host",
+    "api-version",
+    "password",
+    "owner-token",
+    "alias",
+    "uploaded",
+    "delete-at",
+    "sha256",
+    "name",
+    "size",
+    "mime",
+    "downloads",
+    "download-limit",
+    "expiry",
+];
+
+/// A file info report.
+#[derive(Default)]
+struct Report {
+    /// The report fields, in order.
+    fields: Vec<ReportField>,
+}
+
+/// A field in the file info report.
+struct ReportField {
+    /// The field name, as selected with `--field`.
+    name: &'static str,
+
+    /// The label to show in the report, hidden from the report if `None`.
+    label: Option<&'static str>,
+
+    /// The human readable value.
+    value: String,
+
+    /// The machine readable value, if different from the human readable value.
+    ///
+    /// An empty value marks the field as unavailable through `--field`, such as for guessed values.
+    raw: Option<String>,
+}
+
+impl Report {
+    /// Add a field to the report.
+    fn add(&mut self, name: &'static str, label: &'static str, value: String, raw: Option<String>) {
+        self.fields.push(ReportField {
+            name,
+            label: Some(label),
+            value,
+            raw,
+        });
+    }
+
+    /// Add a yes/no field to the report.
+    fn add_bool(&mut self, name: &'static str, label: &'static str, value: bool) {
+        let value = if value { "yes" } else { "no" };
+        self.add(name, label, value.into(), None);
+    }
+
+    /// Add a field that is only available through `--field`.
+    fn add_hidden(&mut self, name: &'static str, raw: String) {
+        self.fields.push(ReportField {
+            name,
+            label: None,
+            value: raw,
+            raw: None,
+        });
+    }
+
+    /// Get the machine readable value of the field with the given name, if available.
+    fn value(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.raw.as_ref().unwrap_or(&field.value).as_str())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Print the report as table.
+    fn print(&self) {
+        let mut table = Table::new();
+        table.set_format(FormatBuilder::new().padding(0, 2).build());
+        for field in &self.fields {
+            if let Some(label) = field.label {
+                table.add_row(Row::new(vec![Cell::new(label), Cell::new(&field.value)]));
+            }
+        }
+        table.printstd();
+    }
+}
+
 #[derive(Debug, Fail)]
 pub enum Error {
     /// Failed to parse a share URL, it was invalid.
@@ -1,3 +1,5 @@
+#[cfg(feature = "clipboard")]
+pub mod clear_clipboard;
 pub mod debug;
 pub mod delete;
 pub mod download;
@@ -8,8 +10,17 @@ pub mod history;
 pub mod info;
 pub mod params;
 pub mod password;
+#[cfg(feature = "qrcode")]
+pub mod qr;
+#[cfg(feature = "history")]
+pub mod queue;
+#[cfg(feature = "history")]
+pub mod reap;
+#[cfg(feature = "history")]
+pub mod schedule_delete;
 pub mod upload;
 pub mod version;
+pub mod watch;
 
 use ffsend_api::action::version::{Error as VersionError, Version as ApiVersion};
 use ffsend_api::api::DesiredVersion;
@@ -17,6 +28,7 @@ use ffsend_api::client::Client;
 use ffsend_api::url::Url;
 
 use crate::config::API_VERSION_ASSUME;
+use crate::retry::Retry;
 use crate::util::print_warning;
 
 /// Based on the given desired API version, select a version we can use.
@@ -24,10 +36,13 @@ use crate::util::print_warning;
 /// If the current desired version is set to the `DesiredVersion::Lookup` variant, this method
 /// will look up the server API version. It it's `DesiredVersion::Use` it will return and
 /// attempt to use the specified version.
+///
+/// The lookup is retried on transient network errors according to the given retry policy.
 fn select_api_version(
     client: &Client,
     host: Url,
     desired: &mut DesiredVersion,
+    retry: &Retry,
 ) -> Result<(), VersionError> {
     // Break if already specified
     if let DesiredVersion::Use(_) = desired {
@@ -37,13 +52,15 @@ fn select_api_version(
     // TODO: only lookup if `DesiredVersion::Assume` after first operation attempt failed
 
     // Look up the version
-    match ApiVersion::new(host).invoke(&client) {
+    match retry.invoke("server version lookup", || {
+        ApiVersion::new(host.clone()).invoke(&client)
+    }) {
         // Use the probed version
         Ok(v) => *desired = DesiredVersion::Use(v),
 
         // If unknown, just assume the default version
         Err(VersionError::Unknown) => {
-            *desired = DesiredVersion::Use(API_VERSION_ASSUME);
+            *desired = DesiredVersion::Assume(API_VERSION_ASSUME);
             print_warning(format!(
                 "server API version could not be determined, assuming v{}",
                 API_VERSION_ASSUME,
@@ -8,6 +8,8 @@ use crate::cmd::matcher::{main::MainMatcher, params::ParamsMatcher, Matcher};
 use crate::error::ActionError;
 #[cfg(feature = "history")]
 use crate::history_tool;
+use crate::limits::Limits;
+use crate::retry::Retry;
 use crate::util::{ensure_owner_token, print_success};
 
 /// A file parameters action.
@@ -42,13 +44,20 @@ impl<'a> Params<'a> {
 
         // Determine the API version to use
         let mut desired_version = matcher_main.api();
-        select_api_version(&client, host, &mut desired_version)?;
+        select_api_version(
+            &client,
+            host.clone(),
+            &mut desired_version,
+            &Retry::new(&matcher_main),
+        )?;
         let api_version = desired_version.version().unwrap();
 
         // Parse the remote file based on the share URL, derive the owner token from history
         let mut file = RemoteFile::parse_url(url, matcher_params.owner())?;
         #[cfg(feature = "history")]
         history_tool::derive_file_properties(&matcher_main, &mut file);
+        #[cfg(feature = "secret-store")]
+        history_tool::derive_owner_token(&matcher_main, &mut file);
 
         // Ensure the owner token is set
         ensure_owner_token(file.owner_token_mut(), &matcher_main, false);
@@ -56,13 +65,12 @@ impl<'a> Params<'a> {
         // We don't authenticate for now
         let auth = false;
 
+        // Get the limits of the host
+        let limits = Limits::for_host(&matcher_main, &client, &host, api_version, auth);
+
         // Build the parameters data object
         let data = ParamsDataBuilder::default()
-            .download_limit(
-                matcher_params
-                    .download_limit(&matcher_main, api_version, auth)
-                    .map(|d| d as u8),
-            )
+            .download_limit(matcher_params.download_limit(&matcher_main, &limits, auth))
             .build()
             .unwrap();
 
@@ -8,6 +8,8 @@ use crate::cmd::matcher::{main::MainMatcher, password::PasswordMatcher, Matcher}
 use crate::error::ActionError;
 #[cfg(feature = "history")]
 use crate::history_tool;
+use crate::passphrase::format_entropy;
+use crate::password_policy::Policy as PasswordPolicy;
 use crate::util::{ensure_owner_token, print_success};
 
 /// A file password action.
@@ -39,6 +41,8 @@ impl<'a> Password<'a> {
         let mut file = RemoteFile::parse_url(url, matcher_password.owner())?;
         #[cfg(feature = "history")]
         history_tool::derive_file_properties(&matcher_main, &mut file);
+        #[cfg(feature = "secret-store")]
+        history_tool::derive_owner_token(&matcher_main, &mut file);
 
         // Ensure the owner token is set
         ensure_owner_token(file.owner_token_mut(), &matcher_main, false);
@@ -46,6 +50,12 @@ impl<'a> Password<'a> {
         // Get the password to use and whether it was generated
         let (password, password_generated) = matcher_password.password();
 
+        // Check a chosen password against the password policy of the host
+        if !password_generated {
+            PasswordPolicy::for_host(&file.host(), matcher_password.password_policy())
+                .check(&password, &matcher_main);
+        }
+
         // Execute an password action
         let result = ApiPassword::new(&file, &password, None).invoke(&client);
         if let Err(PasswordError::Expired) = result {
@@ -67,6 +77,12 @@ impl<'a> Password<'a> {
                 Cell::new("Passphrase:"),
                 Cell::new(&password),
             ]));
+            if let Some(entropy) = matcher_password.passphrase_entropy() {
+                table.add_row(Row::new(vec![
+                    Cell::new("Entropy:"),
+                    Cell::new(&format_entropy(entropy)),
+                ]));
+            }
             table.printstd();
         }
 
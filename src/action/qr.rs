@@ -0,0 +1,29 @@
+use clap::ArgMatches;
+
+use crate::cmd::matcher::{qr::QrMatcher, Matcher};
+use crate::error::ActionError;
+use crate::qrcode;
+
+/// A QR code action.
+pub struct Qr<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> Qr<'a> {
+    /// Construct a new QR code action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the QR code action.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matchers
+        let matcher_qr = QrMatcher::with(self.cmd_matches).unwrap();
+
+        // Render the share URL as QR code
+        qrcode::render(matcher_qr.url().as_str(), &matcher_qr.options())?;
+
+        Ok(())
+    }
+}
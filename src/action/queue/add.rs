@@ -0,0 +1,103 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use clap::ArgMatches;
+use failure::Fail;
+
+use crate::cmd::handler::Handler;
+use crate::cmd::matcher::{main::MainMatcher, queue::add::QueueAddMatcher, Matcher};
+use crate::error::ActionError;
+use crate::queue::Queue;
+use crate::util::{quit_error, quit_error_msg, ErrorHintsBuilder};
+
+/// A queue add action.
+pub struct QueueAdd<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> QueueAdd<'a> {
+    /// Construct a new queue add action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the queue add action.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_add = QueueAddMatcher::with(self.cmd_matches).unwrap();
+
+        // Resolve the absolute file paths, as the queue may be run from anywhere
+        let files: Vec<PathBuf> = matcher_add
+            .files()
+            .into_iter()
+            .map(|file| {
+                if file == "-" {
+                    quit_error_msg(
+                        "can't queue uploading from stdin",
+                        ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                    );
+                }
+                match fs::canonicalize(file) {
+                    Ok(path) => path,
+                    Err(err) => quit_error(
+                        err.context(format!("failed to find file to queue: '{}'", file)),
+                        ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                    ),
+                }
+            })
+            .collect();
+
+        // The upload options must be valid when the queue is run
+        let options = matcher_add.upload_options();
+        Self::check_options(&files, &options);
+
+        // Add the job to the queue
+        let at = matcher_add.at();
+        let id = Queue::update(|queue| queue.add(files, options, at))?;
+
+        if matcher_main.quiet() {
+            println!("{}", id);
+        } else {
+            match at {
+                Some(at) => eprintln!(
+                    "Upload #{} queued for {}",
+                    id,
+                    at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+                ),
+                None => eprintln!("Upload #{} queued", id),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the given upload options are valid, by parsing them as upload command.
+    ///
+    /// If they are invalid, the program quits with the parsing error. Options changing the
+    /// reported share URL into something else are refused, as the queue runner reads the share
+    /// URL from the upload output.
+    fn check_options(files: &[PathBuf], options: &[String]) {
+        let mut args: Vec<OsString> = vec![crate_name!().into(), "upload".into()];
+        args.extend(options.iter().map(OsString::from));
+        args.push("--".into());
+        args.extend(files.iter().map(|file| Path::new(file).into()));
+
+        let matches = match Handler::build().get_matches_from_safe(args) {
+            Ok(matches) => matches,
+            Err(err) => err.exit(),
+        };
+        let matches = matches.subcommand_matches("upload").unwrap();
+        for name in &["recipient", "format-message"] {
+            if matches.occurrences_of(name) > 0 {
+                quit_error_msg(
+                    format!("can't queue uploading with '--{}'", name),
+                    ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                );
+            }
+        }
+    }
+}
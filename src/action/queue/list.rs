@@ -0,0 +1,81 @@
+use chrono::Local;
+use clap::ArgMatches;
+use prettytable::{format::FormatBuilder, Cell, Row, Table};
+
+use crate::cmd::matcher::{main::MainMatcher, queue::list::QueueListMatcher, Matcher};
+use crate::error::ActionError;
+use crate::queue::Queue;
+
+/// A queue list action.
+pub struct QueueList<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> QueueList<'a> {
+    /// Construct a new queue list action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the queue list action.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let _matcher_list = QueueListMatcher::with(self.cmd_matches).unwrap();
+
+        // Load the queue
+        let queue = Queue::load()?;
+        if queue.jobs().is_empty() {
+            if !matcher_main.quiet() {
+                eprintln!("No uploads queued");
+            }
+            return Ok(());
+        }
+
+        // Just list the job IDs in quiet mode
+        if matcher_main.quiet() {
+            queue.jobs().iter().for_each(|job| println!("{}", job.id));
+            return Ok(());
+        }
+
+        // Build the list of column names, upload options may hold secrets so are verbose only
+        let mut columns = vec!["ID", "FILE", "AT", "STATUS"];
+        if matcher_main.verbose() {
+            columns.push("OPTIONS");
+        }
+
+        // Create a new table
+        let mut table = Table::new();
+        table.set_format(FormatBuilder::new().padding(0, 2).build());
+        table.add_row(Row::new(columns.into_iter().map(Cell::new).collect()));
+
+        // Add an entry for each job
+        for job in queue.jobs() {
+            let files: Vec<_> = job.files.iter().map(|f| f.to_string_lossy()).collect();
+            let at = job
+                .at
+                .map(|at| {
+                    at.with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_else(|| "-".into());
+            let status = match &job.error {
+                Some(error) => format!("failed {}x: {}", job.attempts, error),
+                None => "queued".into(),
+            };
+
+            let mut cells = vec![format!("#{}", job.id), files.join(" "), at, status];
+            if matcher_main.verbose() {
+                cells.push(job.args.join(" "));
+            }
+            table.add_row(Row::new(cells.into_iter().map(|c| Cell::new(&c)).collect()));
+        }
+
+        // Print the table
+        table.printstd();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+pub mod add;
+pub mod list;
+pub mod remove;
+pub mod run;
+
+use clap::ArgMatches;
+
+use crate::cmd::matcher::{queue::QueueMatcher, Matcher};
+use crate::error::ActionError;
+use add::QueueAdd;
+use list::QueueList;
+use remove::QueueRemove;
+use run::QueueRun;
+
+/// An upload queue action.
+pub struct Queue<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> Queue<'a> {
+    /// Construct a new queue action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the queue action.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matcher
+        let matcher_queue = QueueMatcher::with(self.cmd_matches).unwrap();
+
+        // Match the queue sub commands
+        if matcher_queue.matcher_add().is_some() {
+            return QueueAdd::new(self.cmd_matches).invoke();
+        }
+        if matcher_queue.matcher_list().is_some() {
+            return QueueList::new(self.cmd_matches).invoke();
+        }
+        if matcher_queue.matcher_remove().is_some() {
+            return QueueRemove::new(self.cmd_matches).invoke();
+        }
+        if matcher_queue.matcher_run().is_some() {
+            return QueueRun::new(self.cmd_matches).invoke();
+        }
+
+        // Unreachable, clap will print help for missing sub command instead
+        unreachable!()
+    }
+}
@@ -0,0 +1,44 @@
+use clap::ArgMatches;
+
+use crate::cmd::matcher::{main::MainMatcher, queue::remove::QueueRemoveMatcher, Matcher};
+use crate::error::ActionError;
+use crate::queue::Queue;
+use crate::util::{quit_error_msg, ErrorHintsBuilder};
+
+/// A queue remove action.
+pub struct QueueRemove<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> QueueRemove<'a> {
+    /// Construct a new queue remove action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the queue remove action.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_remove = QueueRemoveMatcher::with(self.cmd_matches).unwrap();
+
+        // Remove the jobs, all must exist
+        Queue::update(|queue| {
+            for id in matcher_remove.ids() {
+                if !queue.remove(id) {
+                    quit_error_msg(
+                        format!("no queued upload with ID #{}", id),
+                        ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                    );
+                }
+            }
+        })?;
+
+        if !matcher_main.quiet() {
+            eprintln!("Removed from queue");
+        }
+
+        Ok(())
+    }
+}
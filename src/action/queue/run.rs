@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+use std::env::current_exe;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use chrono::{Local, Utc};
+use clap::ArgMatches;
+use regex::Regex;
+
+use crate::cmd::matcher::{main::MainMatcher, queue::run::QueueRunMatcher, Matcher};
+use crate::error::ActionError;
+use crate::queue::{Job, Queue};
+use crate::util::{print_error_msg, quit_error_msg, ErrorHintsBuilder};
+
+/// A queue run action.
+pub struct QueueRun<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> QueueRun<'a> {
+    /// Construct a new queue run action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the queue run action.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_run = QueueRunMatcher::with(self.cmd_matches).unwrap();
+
+        // Lock the queue while running, so jobs aren't uploaded twice
+        let _lock = Queue::lock()?;
+
+        // Run each job at most once per run, failed jobs stay in the queue for the next run
+        let mut done = HashSet::new();
+        let mut failed = 0;
+        loop {
+            // Reload the queue each time, jobs may be added or removed while running
+            let queue = Queue::load()?;
+            let now = Utc::now();
+            let pending: Vec<&Job> = queue
+                .jobs()
+                .iter()
+                .filter(|job| !done.contains(&job.id))
+                .collect();
+
+            // Find the next job that is due, or wait for the earliest one if requested
+            let job = match pending.iter().find(|job| matcher_run.all() || job.due(now)) {
+                Some(job) => (*job).clone(),
+                None => {
+                    let next = pending.iter().filter_map(|job| job.at).min();
+                    match next {
+                        Some(at) if matcher_run.wait() => {
+                            if matcher_main.verbose() {
+                                eprintln!(
+                                    "Waiting until {} for next queued upload...",
+                                    at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+                                );
+                            }
+                            if let Ok(delay) = (at - now).to_std() {
+                                thread::sleep(delay);
+                            }
+                            continue;
+                        }
+                        _ => break,
+                    }
+                }
+            };
+            done.insert(job.id);
+
+            if failed + done.len() > 1 && !matcher_main.quiet() {
+                eprintln!();
+            }
+            if !matcher_main.quiet() {
+                eprintln!("Uploading #{}...", job.id);
+            }
+
+            // Attempt the upload, retry on failure
+            let attempts = matcher_run.attempts();
+            let mut result = Err(String::new());
+            for attempt in 1..=attempts {
+                result = Self::upload(&matcher_main, &job);
+                match &result {
+                    Ok(_) => break,
+                    Err(err) => {
+                        if !matcher_main.quiet() {
+                            print_error_msg(format!(
+                                "upload #{} failed (attempt {}/{}): {}",
+                                job.id, attempt, attempts, err,
+                            ));
+                        }
+                        if attempt < attempts {
+                            thread::sleep(matcher_run.retry_delay());
+                        }
+                    }
+                }
+            }
+
+            // Update the queue and write the result to the log
+            match result {
+                Ok(url) => {
+                    Queue::update(|queue| queue.remove(job.id))?;
+                    Queue::log(&job, "uploaded", &url)?;
+
+                    if matcher_main.quiet() {
+                        println!("{}", url);
+                    } else {
+                        eprintln!("Uploaded #{}: {}", job.id, url);
+                    }
+                }
+                Err(err) => {
+                    failed += 1;
+                    Queue::update(|queue| {
+                        if let Some(queued) = queue.get_mut(job.id) {
+                            queued.attempts += attempts;
+                            queued.error = Some(err.clone());
+                        }
+                    })?;
+                    Queue::log(&job, "failed", &err)?;
+                }
+            }
+        }
+
+        if done.is_empty() && !matcher_main.quiet() {
+            eprintln!("No queued uploads due");
+        }
+
+        // Report failed jobs
+        if failed > 0 {
+            quit_error_msg(
+                format!(
+                    "failed to upload {} of {} queued uploads, they remain queued",
+                    failed,
+                    done.len(),
+                ),
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Upload the files of the given job, by invoking the upload command of this binary.
+    ///
+    /// Returns the share URL on success, or the reported error on failure.
+    fn upload(matcher_main: &MainMatcher, job: &Job) -> Result<String, String> {
+        let exe = current_exe().map_err(|err| format!("failed to find ffsend binary: {}", err))?;
+
+        // Build the command, use the same history file as this invocation
+        let mut cmd = Command::new(exe);
+        cmd.args(["--quiet", "--no-interact"])
+            .arg("--history")
+            .arg(matcher_main.history());
+        if matcher_main.incognito() {
+            cmd.arg("--incognito");
+        }
+        // Don't render a share message from the environment, the share URL is read from stdout
+        cmd.arg("upload")
+            .args(&job.args)
+            .arg("--")
+            .args(&job.files)
+            .env_remove("FFSEND_FORMAT_MESSAGE")
+            .stdin(Stdio::null());
+
+        let output = cmd
+            .output()
+            .map_err(|err| format!("failed to invoke upload: {}", err))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        // Find the share URL in the output on success
+        if output.status.success() {
+            return stdout
+                .split_whitespace()
+                .find(|token| token.contains("://"))
+                .map(|url| url.to_owned())
+                .ok_or_else(|| "upload succeeded but did not report a share URL".into());
+        }
+
+        // Collect the reported error and its causes, without colors
+        let colors = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+        let stderr = colors.replace_all(&stderr, "");
+        let error: Vec<&str> = stderr
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| line.starts_with("error:") || line.starts_with("caused by:"))
+            .map(|line| line.trim_start_matches("error:").trim())
+            .collect();
+        if error.is_empty() {
+            Err(format!("upload exited with {}", output.status))
+        } else {
+            Err(error.join(", "))
+        }
+    }
+}
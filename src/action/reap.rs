@@ -0,0 +1,112 @@
+use chrono::Utc;
+use clap::ArgMatches;
+use failure::Fail;
+use ffsend_api::action::delete::{Delete as ApiDelete, Error as DeleteError};
+use ffsend_api::file::remote_file::RemoteFile;
+
+use crate::client::create_config;
+use crate::cmd::matcher::{main::MainMatcher, reap::ReapMatcher, Matcher};
+use crate::error::ActionError;
+use crate::history_tool;
+use crate::util::{print_error, print_error_msg, quit_error_msg, ErrorHintsBuilder};
+
+/// A reap action, deleting files past their scheduled deletion time.
+pub struct Reap<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> Reap<'a> {
+    /// Construct a new reap action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the reap action.
+    ///
+    /// Each file is deleted separately, failures are reported per file and don't stop other files
+    /// from being deleted. The program quits with an error if any file failed to delete.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_reap = ReapMatcher::with(self.cmd_matches).unwrap();
+
+        // Collect the files due for deletion with their label, release the history before deleting
+        let now = Utc::now();
+        let files: Vec<(RemoteFile, String)> = {
+            let history = history_tool::load_with_owner_tokens(&matcher_main);
+            history
+                .files_sorted()
+                .into_iter()
+                .filter(|file| {
+                    history
+                        .meta(file.id())
+                        .and_then(|meta| meta.delete_at)
+                        .map(|delete_at| delete_at <= now)
+                        .unwrap_or(false)
+                })
+                .map(|file| (file.clone(), history.label(file)))
+                .collect()
+        };
+
+        // There must be files to delete
+        if files.is_empty() {
+            if !matcher_main.quiet() {
+                eprintln!("No files due for deletion");
+            }
+            return Ok(());
+        }
+
+        // Only list the files in a dry run
+        if matcher_reap.dry_run() {
+            files.iter().for_each(|(_, label)| println!("{}", label));
+            return Ok(());
+        }
+
+        // Create client
+        let client_config = create_config(&matcher_main);
+        let client = client_config.client(false);
+
+        // Delete each file, report the result
+        let mut failed = 0;
+        for (file, label) in &files {
+            if !file.has_owner_token() {
+                failed += 1;
+                print_error_msg(format!(
+                    "no owner token known for '{}', can't delete",
+                    label
+                ));
+                continue;
+            }
+
+            match ApiDelete::new(file, None).invoke(&client) {
+                Ok(()) => {
+                    history_tool::remove(&matcher_main, file);
+                    if !matcher_main.quiet() {
+                        eprintln!("Deleted: {}", label);
+                    }
+                }
+                Err(DeleteError::Expired) => {
+                    history_tool::remove(&matcher_main, file);
+                    if !matcher_main.quiet() {
+                        eprintln!("Expired: {}", label);
+                    }
+                }
+                Err(err) => {
+                    failed += 1;
+                    print_error(err.context(format!("failed to delete '{}'", label)));
+                }
+            }
+        }
+
+        // Quit with an error if any file failed to delete
+        if failed > 0 {
+            quit_error_msg(
+                format!("failed to delete {} of {} files", failed, files.len()),
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            );
+        }
+
+        Ok(())
+    }
+}
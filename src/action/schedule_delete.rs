@@ -0,0 +1,134 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use clap::ArgMatches;
+use failure::Fail;
+use ffsend_api::action::exists::Exists as ApiExists;
+use ffsend_api::action::info::Info as ApiInfo;
+use ffsend_api::file::remote_file::RemoteFile;
+
+use crate::client::create_config;
+use crate::cmd::matcher::{main::MainMatcher, schedule_delete::ScheduleDeleteMatcher, Matcher};
+use crate::error::ActionError;
+use crate::history_tool;
+use crate::util::{
+    bin_name, ensure_owner_token, format_duration, highlight, print_error, print_success,
+    print_warning, quit_error, quit_error_msg, ErrorHintsBuilder,
+};
+
+/// A schedule delete action.
+pub struct ScheduleDelete<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> ScheduleDelete<'a> {
+    /// Construct a new schedule delete action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the schedule delete action.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_schedule = ScheduleDeleteMatcher::with(self.cmd_matches).unwrap();
+
+        // The deletion time is kept in history
+        if matcher_main.incognito() {
+            quit_error_msg(
+                "can't schedule deletion in incognito mode, it is kept in history",
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            );
+        }
+
+        // Parse the remote file based on the share link, derive the owner token from history
+        let mut file = RemoteFile::parse_url(matcher_schedule.url(), matcher_schedule.owner())?;
+        history_tool::derive_file_properties(&matcher_main, &mut file);
+        #[cfg(feature = "secret-store")]
+        history_tool::derive_owner_token(&matcher_main, &mut file);
+
+        // Cancel a scheduled deletion
+        let delete_at = match matcher_schedule.delete_at() {
+            Some(delete_at) => delete_at,
+            None => {
+                let scheduled = history_tool::lookup(&matcher_main, &file)
+                    .and_then(|(_, meta)| meta)
+                    .and_then(|meta| meta.delete_at)
+                    .is_some();
+                if !scheduled {
+                    quit_error_msg(
+                        "the file is not scheduled for deletion",
+                        ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                    );
+                }
+                Self::save(&matcher_main, file, None);
+                print_success("Scheduled deletion cancelled");
+                return Ok(());
+            }
+        };
+
+        // The deletion time must be in the future
+        if delete_at <= Utc::now() {
+            quit_error_msg(
+                "the deletion time must be in the future",
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            );
+        }
+
+        // The owner token is required to delete the file later
+        ensure_owner_token(file.owner_token_mut(), &matcher_main, false);
+
+        // Create client
+        let client_config = create_config(&matcher_main);
+        let client = client_config.client(false);
+
+        // The file must exist, update its expiry time
+        if !ApiExists::new(&file).invoke(&client)?.exists() {
+            history_tool::remove(&matcher_main, &file);
+            quit_error_msg(
+                "the file has expired or did never exist",
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            );
+        }
+        match ApiInfo::new(&file, None).invoke(&client) {
+            Ok(info) => {
+                file.set_expire_duration(Duration::milliseconds(info.ttl_millis() as i64));
+                if Utc::now() + file.expire_duration() < delete_at {
+                    print_warning("the file expires before the scheduled deletion time");
+                }
+            }
+            Err(err) => print_error(err.context("failed to fetch file expiry time, ignoring")),
+        }
+
+        // Schedule the deletion
+        Self::save(&matcher_main, file, Some(delete_at));
+        if !matcher_main.quiet() {
+            eprintln!(
+                "Deletion scheduled at {} (in {})",
+                delete_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+                format_duration(delete_at.signed_duration_since(Utc::now())),
+            );
+            eprintln!(
+                "Run '{}' regularly to delete files that are due, such as from cron",
+                highlight(&format!("{} reap", bin_name())),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Save the deletion time for the given file in history.
+    ///
+    /// If this fails, the program quits with an error message.
+    fn save(matcher_main: &MainMatcher, file: RemoteFile, delete_at: Option<DateTime<Utc>>) {
+        if let Err(err) = history_tool::schedule_delete(matcher_main, file, delete_at) {
+            quit_error(
+                err.context("failed to save scheduled deletion to history"),
+                ErrorHintsBuilder::default()
+                    .history(true)
+                    .verbose(false)
+                    .build()
+                    .unwrap(),
+            );
+        }
+    }
+}
@@ -1,41 +1,58 @@
 use std::env::current_dir;
 use std::fs;
 use std::io::{Error as IoError, Write};
-use std::path::Path;
-#[cfg(feature = "archive")]
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 #[cfg(feature = "archive")]
 use std::process::exit;
 use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "history")]
+use chrono::{DateTime, Utc};
 use clap::ArgMatches;
 use failure::Fail;
-use ffsend_api::action::params::ParamsDataBuilder;
+use ffsend_api::action::params::{ParamsData, ParamsDataBuilder};
 use ffsend_api::action::upload::{Error as UploadError, Upload as ApiUpload};
 use ffsend_api::action::version::Error as VersionError;
-use ffsend_api::config::{upload_size_max, UPLOAD_SIZE_MAX_RECOMMENDED};
+use ffsend_api::api::Version;
+use ffsend_api::client::Client;
+use ffsend_api::config::UPLOAD_SIZE_MAX_RECOMMENDED;
+use ffsend_api::file::remote_file::RemoteFile;
 use ffsend_api::pipe::ProgressReporter;
+use ffsend_api::url::Url;
 use pathdiff::diff_paths;
 use prettytable::{format::FormatBuilder, Cell, Row, Table};
-#[cfg(feature = "qrcode")]
-use qr2term::print_qr;
 use tempfile::{Builder as TempBuilder, NamedTempFile};
 
 use super::select_api_version;
 #[cfg(feature = "archive")]
 use crate::archive::archiver::Archiver;
 use crate::client::create_config;
-use crate::cmd::matcher::{MainMatcher, Matcher, UploadMatcher};
+use crate::cmd::arg::ArgDownloadLimit;
+use crate::cmd::matcher::{upload::Recipient, MainMatcher, Matcher, UploadMatcher};
+#[cfg(feature = "history")]
+use crate::history::FileMeta;
 #[cfg(feature = "history")]
 use crate::history_tool;
-use crate::progress::ProgressBar;
+use crate::limits::Limits;
+use crate::manifest::{self, Manifest};
+#[cfg(feature = "clipboard")]
+use crate::message::CopyMode;
+use crate::message::ShareInfo;
+use crate::passphrase::format_entropy;
+use crate::password_policy::Policy as PasswordPolicy;
+use crate::progress::{ChecksumReporter, ProgressBar};
+#[cfg(feature = "qrcode")]
+use crate::qrcode;
+use crate::retry::Retry;
 #[cfg(feature = "urlshorten")]
 use crate::urlshorten;
+#[cfg(feature = "history")]
+use crate::util::print_warning;
 #[cfg(feature = "clipboard")]
 use crate::util::set_clipboard;
 use crate::util::{
-    format_bytes, open_url, print_error, print_error_msg, prompt_yes, quit, quit_error_msg,
-    rand_alphanum_string, stdin_read_file, ErrorHintsBuilder, StdinErr,
+    format_bytes, highlight, open_url, print_error, print_error_msg, prompt_yes, quit,
+    quit_error_msg, rand_alphanum_string, stdin_read_file, ErrorHintsBuilder, StdinErr,
 };
 
 /// A file upload action.
@@ -56,6 +73,42 @@ impl<'a> Upload<'a> {
         let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
         let matcher_upload = UploadMatcher::with(self.cmd_matches).unwrap();
 
+        // Select the URL shortener early, to quit on misconfiguration before uploading
+        #[cfg(feature = "urlshorten")]
+        let shortener = if matcher_upload.shorten() {
+            Some(matcher_upload.shortener())
+        } else {
+            None
+        };
+
+        // Get what to mail and the message template early, to quit on misconfiguration before
+        // uploading
+        #[cfg(feature = "mail")]
+        let mail = matcher_upload.mail();
+        let message_template = matcher_upload.format_message();
+
+        // Get the password early, to quit on conflicting password arguments before uploading,
+        // recipient shares each get their own generated passphrase instead
+        let recipients = matcher_upload.recipients();
+        let password = if recipients.is_empty() {
+            matcher_upload.password()
+        } else {
+            None
+        };
+
+        // Get the scheduled deletion time early, it is kept in history
+        #[cfg(feature = "history")]
+        let delete_at = matcher_upload.delete_at();
+        #[cfg(feature = "history")]
+        {
+            if delete_at.is_some() && matcher_main.incognito() {
+                quit_error_msg(
+                    "can't schedule deletion in incognito mode, it is kept in history",
+                    ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+                );
+            }
+        }
+
         // The file name to use
         #[allow(unused_mut)]
         let mut file_name = matcher_upload.name().map(|s| s.to_owned());
@@ -260,20 +313,23 @@ impl<'a> Upload<'a> {
         // Create a reqwest client capable for uploading files
         let client_config = create_config(&matcher_main);
         let client = client_config.clone().client(false);
+        let retry = Retry::new(&matcher_main);
 
         // Determine the API version to use
         let mut desired_version = matcher_main.api();
-        select_api_version(&client, host.clone(), &mut desired_version)?;
+        select_api_version(&client, host.clone(), &mut desired_version, &retry)?;
         let api_version = desired_version.version().unwrap();
 
         // We do not authenticate for now
         let auth = false;
 
+        // Get the limits of the host, to check against before uploading
+        let limits = Limits::for_host(&matcher_main, &client, &host, api_version, auth);
+
         // TODO: extract this into external function
         {
             // Determine the max file size
-            // TODO: set false parameter to authentication state
-            let max_size = upload_size_max(api_version, auth);
+            let max_size = limits.size_max;
 
             // Get the file size, fail on empty files, warn about large files
             if let Ok(size) = path.metadata().map(|m| m.len()) {
@@ -289,20 +345,36 @@ impl<'a> Upload<'a> {
                     )
                 }
 
+                // When splitting, the size limits apply to each part
+                let (size, subject) = match matcher_upload.split() {
+                    Some(part_size) if size > part_size => (part_size, "split part size"),
+                    _ => (size, "file size"),
+                };
+
                 // Enforce maximum file size
                 if size > max_size && !matcher_main.force() {
                     // The file is too large, show an error and quit
+                    let mut hints = ErrorHintsBuilder::default();
+                    if matcher_upload.split().is_none() {
+                        hints = hints.add_info(format!(
+                            "Use '{}' to upload the file in parts of at most {}",
+                            highlight("--split SIZE"),
+                            format_bytes(max_size),
+                        ));
+                    }
                     quit_error_msg(
                         format!(
-                            "the file size is {}, bigger than the maximum allowed of {}",
+                            "the {} is {}, bigger than the maximum allowed of {}{}",
+                            subject,
                             format_bytes(size),
                             format_bytes(max_size),
+                            if limits.from_host {
+                                " on this host"
+                            } else {
+                                ""
+                            },
                         ),
-                        ErrorHintsBuilder::default()
-                            .force(true)
-                            .verbose(false)
-                            .build()
-                            .unwrap(),
+                        hints.force(true).verbose(false).build().unwrap(),
                     );
                 }
 
@@ -310,7 +382,8 @@ impl<'a> Upload<'a> {
                 if size > UPLOAD_SIZE_MAX_RECOMMENDED && !matcher_main.force() {
                     // The file is larger than the recommended maximum, warn
                     eprintln!(
-                        "The file size is {}, bigger than the recommended maximum of {}",
+                        "The {} is {}, bigger than the recommended maximum of {}",
+                        subject,
                         format_bytes(size),
                         format_bytes(UPLOAD_SIZE_MAX_RECOMMENDED),
                     );
@@ -338,12 +411,8 @@ impl<'a> Upload<'a> {
         let params = {
             // Build the parameters data object
             let params = ParamsDataBuilder::default()
-                .download_limit(
-                    matcher_upload
-                        .download_limit(&matcher_main, api_version, auth)
-                        .map(|d| d as u8),
-                )
-                .expiry_time(matcher_upload.expiry_time(&matcher_main, api_version, auth))
+                .download_limit(matcher_upload.download_limit(&matcher_main, &limits, auth))
+                .expiry_time(matcher_upload.expiry_time(&matcher_main, &limits, auth))
                 .build()
                 .unwrap();
 
@@ -355,38 +424,146 @@ impl<'a> Upload<'a> {
             }
         };
 
+        // The download limit of the share, to render share messages with
+        let downloads = params
+            .as_ref()
+            .and_then(|params| params.download_limit)
+            .map(|downloads| downloads as usize)
+            .unwrap_or(limits.downloads_default);
+
         // Build the progress reporter
         let progress_reporter: Arc<Mutex<dyn ProgressReporter>> = progress_bar;
 
+        // Upload a separate share for each recipient
+        if !recipients.is_empty() {
+            let reporter = if !matcher_main.quiet() {
+                Some(&progress_reporter)
+            } else {
+                None
+            };
+            Self::upload_recipients(
+                &matcher_main,
+                &matcher_upload,
+                api_version,
+                host,
+                &path,
+                file_name,
+                &recipients,
+                params,
+                message_template.as_deref(),
+                &limits,
+                auth,
+                &transfer_client,
+                reporter,
+            )?;
+
+            // Temporary files are removed when dropped
+            if matcher_upload.delete() {
+                Self::delete_local(&paths);
+            }
+            return Ok(());
+        }
+
         // Get the password to use and whether it was generated
-        let password = matcher_upload.password();
         let (password, password_generated) =
             password.map(|(p, g)| (Some(p), g)).unwrap_or((None, false));
 
+        // Check a chosen password against the password policy of the host
+        if let Some(password) = password.as_ref().filter(|_| !password_generated) {
+            PasswordPolicy::for_host(&host, matcher_upload.password_policy())
+                .check(password, &matcher_main);
+        }
+
+        // The name of the uploaded file, to reference it by in history and share messages
+        let share_name = file_name.clone().or_else(|| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.to_owned())
+        });
+
         // Execute an upload action, obtain the URL
         let reporter = if !matcher_main.quiet() {
             Some(&progress_reporter)
         } else {
             None
         };
-        let file = ApiUpload::new(
-            api_version,
-            host,
-            path.clone(),
-            file_name,
-            password.clone(),
-            params,
-        )
-        .invoke(&transfer_client, reporter)?;
+        let split = matcher_upload.split().filter(|part_size| {
+            path.metadata()
+                .map(|m| m.len() > *part_size)
+                .unwrap_or(false)
+        });
+
+        // Compute the file checksum while uploading, split uploads compute it while splitting
+        let checksum_reporter = if matcher_upload.checksum() && split.is_none() {
+            Some(Arc::new(Mutex::new(ChecksumReporter::new(&path, reporter))))
+        } else {
+            None
+        };
+        let checksum_progress: Option<Arc<Mutex<dyn ProgressReporter>>> = checksum_reporter
+            .clone()
+            .map(|reporter| reporter as Arc<Mutex<dyn ProgressReporter>>);
+        let reporter = checksum_progress.as_ref().or(reporter);
+
+        let (file, checksum) = match split {
+            Some(part_size) => {
+                let (file, checksum) = Self::upload_split(
+                    &matcher_main,
+                    &matcher_upload,
+                    api_version,
+                    host,
+                    &path,
+                    file_name,
+                    password.clone(),
+                    params,
+                    part_size,
+                    &transfer_client,
+                    reporter,
+                )?;
+                (file, Some(checksum).filter(|_| matcher_upload.checksum()))
+            }
+            None => {
+                let file = retry.invoke("upload", || {
+                    ApiUpload::new(
+                        api_version,
+                        host.clone(),
+                        path.clone(),
+                        file_name.clone(),
+                        password.clone(),
+                        params.clone(),
+                    )
+                    .invoke(&transfer_client, reporter)
+                })?;
+                (
+                    file,
+                    checksum_reporter.and_then(|r| Self::take_checksum(&r)),
+                )
+            }
+        };
         #[allow(unused_mut)]
         let mut url = file.download_url(true);
 
+        // The secret key, if shown separately from the share URL
+        #[allow(unused_mut)]
+        let mut key: Option<String> = None;
+
         // Shorten the share URL if requested, prompt the user to confirm
         #[cfg(feature = "urlshorten")]
         {
-            if matcher_upload.shorten() {
-                if prompt_yes("URL shortening is a security risk. This shares the secret URL with a 3rd party.\nDo you want to shorten the share URL?", Some(false), &matcher_main) {
-                    match urlshorten::shorten_url(&client, &url) {
+            if let Some(shortener) = shortener {
+                if matcher_upload.shorten_split() {
+                    // Only share the URL without secret key with the shortener
+                    match urlshorten::shorten_url(&client, &shortener, &file.download_url(false)) {
+                        Ok(short) => {
+                            url = short;
+                            key = Some(file.secret());
+                        }
+                        Err(err) => print_error(
+                            err.context("failed to shorten share URL, ignoring")
+                                .compat(),
+                        ),
+                    }
+                } else if prompt_yes(&format!("URL shortening is a security risk. This shares the secret URL with {}.\nDo you want to shorten the share URL?", shortener.name()), Some(false), &matcher_main) {
+                    match urlshorten::shorten_url(&client, &shortener, &url) {
                         Ok(short) => url = short,
                         Err(err) => print_error(
                             err.context("failed to shorten share URL, ignoring")
@@ -397,8 +574,27 @@ impl<'a> Upload<'a> {
             }
         }
 
-        // Report the result
-        if !matcher_main.quiet() {
+        // Describe the share to render messages with, include the secret key if shown separately
+        let info = ShareInfo {
+            url: if key.is_some() {
+                file.download_url(true)
+            } else {
+                url.clone()
+            },
+            name: share_name.clone(),
+            size: path.metadata().ok().map(|metadata| metadata.len()),
+            expiry: Some(file.expire_duration()),
+            downloads: Some(downloads),
+            password: password.clone(),
+            owner: file.owner_token().cloned(),
+            recipient: None,
+        };
+        let message = message_template.map(|template| info.render(&template));
+
+        // Report the result, or print the share message
+        if let Some(message) = &message {
+            println!("{}", message);
+        } else if !matcher_main.quiet() {
             // Create a table
             let mut table = Table::new();
             table.set_format(FormatBuilder::new().padding(0, 2).build());
@@ -422,12 +618,23 @@ impl<'a> Upload<'a> {
                     Cell::new(url.as_str()),
                 ]));
 
-                // Show a generate passphrase
+                // Show the separate secret key
+                if let Some(key) = &key {
+                    table.add_row(Row::new(vec![Cell::new("Secret key:"), Cell::new(key)]));
+                }
+
+                // Show a generate passphrase and its estimated entropy
                 if password_generated {
                     table.add_row(Row::new(vec![
                         Cell::new("Passphrase:"),
-                        Cell::new(&password.unwrap_or("?".into())),
+                        Cell::new(password.as_deref().unwrap_or("?")),
                     ]));
+                    if let Some(entropy) = matcher_upload.passphrase_entropy() {
+                        table.add_row(Row::new(vec![
+                            Cell::new("Entropy:"),
+                            Cell::new(&format_entropy(entropy)),
+                        ]));
+                    }
                 }
 
                 // Show the owner token
@@ -435,39 +642,105 @@ impl<'a> Upload<'a> {
                     Cell::new("Owner token:"),
                     Cell::new(file.owner_token().unwrap()),
                 ]));
+
+                // Show the file checksum
+                if let Some(checksum) = &checksum {
+                    table.add_row(Row::new(vec![Cell::new("SHA-256:"), Cell::new(checksum)]));
+                }
             } else {
                 table.add_row(Row::new(vec![Cell::new(url.as_str())]));
 
-                // Show a generate passphrase
+                // Show the separate secret key
+                if let Some(key) = &key {
+                    table.add_row(Row::new(vec![Cell::new(key)]));
+                }
+
+                // Show a generate passphrase and its estimated entropy
                 if password_generated {
-                    table.add_row(Row::new(vec![Cell::new(&password.unwrap_or("?".into()))]));
+                    let mut cells = vec![Cell::new(password.as_deref().unwrap_or("?"))];
+                    if let Some(entropy) = matcher_upload.passphrase_entropy() {
+                        cells.push(Cell::new(&format_entropy(entropy)));
+                    }
+                    table.add_row(Row::new(cells));
+                }
+
+                // Show the file checksum
+                if let Some(checksum) = &checksum {
+                    table.add_row(Row::new(vec![Cell::new(checksum)]));
                 }
             }
 
             table.printstd();
         } else {
             println!("{}", url);
+            if let Some(key) = &key {
+                println!("{}", key);
+            }
+            if let Some(checksum) = &checksum {
+                println!("{}", checksum);
+            }
+        }
+
+        // Mail the share link
+        #[cfg(feature = "mail")]
+        {
+            if let Some(mail) = &mail {
+                match mail.send(&info) {
+                    Ok(()) => {
+                        if !matcher_main.quiet() {
+                            eprintln!("Share link mailed");
+                        }
+                    }
+                    Err(err) => print_error(err.context("failed to mail the share link, ignoring")),
+                }
+            }
         }
 
         // Add the file to the history manager
         #[cfg(feature = "history")]
-        history_tool::add(&matcher_main, file.clone(), false);
+        history_tool::add_meta(
+            &matcher_main,
+            file.clone(),
+            FileMeta {
+                name: share_name,
+                sha256: checksum,
+                delete_at,
+                ..FileMeta::default()
+            },
+            false,
+        );
+        #[cfg(feature = "history")]
+        warn_expires_before(&file, delete_at);
 
-        // Open the URL in the browser
+        // Open the URL in the browser, include the secret key if shown separately
         if matcher_upload.open() {
+            let url = if key.is_some() {
+                file.download_url(true)
+            } else {
+                url.clone()
+            };
             if let Err(err) = open_url(&url) {
                 print_error(err.context("failed to open the share link in the browser"));
             };
         }
 
-        // Copy the URL or command to the user's clipboard
+        // Copy the selected property or share message to the user's clipboard
         #[cfg(feature = "clipboard")]
         {
             if let Some(copy_mode) = matcher_upload.copy() {
-                if let Err(err) = set_clipboard(copy_mode.build(url.as_str())) {
-                    print_error(
-                        err.context("failed to copy the share link to the clipboard, ignoring"),
-                    );
+                match copy_mode.build(&info, url.as_str(), key.as_deref()) {
+                    Some(content) => {
+                        if let Err(err) = set_clipboard(content, &matcher_main) {
+                            print_error(err.context(format!(
+                                "failed to copy the {} to the clipboard, ignoring",
+                                copy_mode.describe(),
+                            )));
+                        }
+                    }
+                    None => print_error_msg(format!(
+                        "no {} to copy to the clipboard, ignoring",
+                        copy_mode.describe(),
+                    )),
                 }
             }
         }
@@ -476,7 +749,7 @@ impl<'a> Upload<'a> {
         #[cfg(feature = "qrcode")]
         {
             if matcher_upload.qrcode() {
-                if let Err(err) = print_qr(url.as_str()) {
+                if let Err(err) = qrcode::render(url.as_str(), &matcher_upload.qrcode_options()) {
                     print_error(err.context("failed to print QR code, ignoring").compat());
                 }
             }
@@ -507,28 +780,382 @@ impl<'a> Upload<'a> {
 
         // Delete local files after uploading
         if matcher_upload.delete() {
-            for path in &paths {
-                if path.is_file() {
-                    if let Err(err) = fs::remove_file(path) {
-                        print_error(
-                            Error::Delete(err)
-                                .context("failed to delete local file after upload, ignoring")
-                                .compat(),
-                        );
-                    }
-                } else {
-                    if let Err(err) = fs::remove_dir_all(path) {
-                        print_error(
-                            Error::Delete(err)
-                                .context("failed to delete local directory after upload, ignoring")
-                                .compat(),
-                        );
+            Self::delete_local(&paths);
+        }
+
+        Ok(())
+    }
+
+    /// Delete the given local files or directories after uploading.
+    ///
+    /// Failures are reported and ignored.
+    fn delete_local(paths: &[PathBuf]) {
+        for path in paths {
+            if path.is_file() {
+                if let Err(err) = fs::remove_file(path) {
+                    print_error(
+                        Error::Delete(err)
+                            .context("failed to delete local file after upload, ignoring")
+                            .compat(),
+                    );
+                }
+            } else {
+                if let Err(err) = fs::remove_dir_all(path) {
+                    print_error(
+                        Error::Delete(err)
+                            .context("failed to delete local directory after upload, ignoring")
+                            .compat(),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Upload the file at the given `path` as separate share for each of the given recipients.
+    ///
+    /// Each share is protected with its own generated passphrase, and is labelled with the
+    /// recipient in history. The shares are reported once uploaded, also if uploading a later
+    /// share failed.
+    #[allow(clippy::too_many_arguments)]
+    fn upload_recipients(
+        matcher_main: &MainMatcher,
+        matcher_upload: &UploadMatcher,
+        api_version: Version,
+        host: Url,
+        path: &Path,
+        file_name: Option<String>,
+        recipients: &[Recipient],
+        params: Option<ParamsData>,
+        message_template: Option<&str>,
+        limits: &Limits,
+        auth: bool,
+        client: &Client,
+        reporter: Option<&Arc<Mutex<dyn ProgressReporter>>>,
+    ) -> Result<(), Error> {
+        // Check the recipient download limits before uploading anything
+        let recipient_params: Vec<Option<ParamsData>> = recipients
+            .iter()
+            .map(|recipient| match recipient.downloads {
+                Some(downloads) => {
+                    let downloads = ArgDownloadLimit::check(downloads, matcher_main, limits, auth);
+                    let mut params = params.clone().unwrap_or_default();
+                    params.download_limit = if usize::from(downloads) != limits.downloads_default {
+                        Some(downloads)
+                    } else {
+                        None
+                    };
+                    Some(params).filter(|params| !params.is_empty())
+                }
+                None => params.clone(),
+            })
+            .collect();
+
+        // Get the scheduled deletion time for all shares
+        #[cfg(feature = "history")]
+        let delete_at = matcher_upload.delete_at();
+
+        // Compute the file checksum once for all shares, while uploading the first
+        let mut checksum_reporter = if matcher_upload.checksum() {
+            Some(Arc::new(Mutex::new(ChecksumReporter::new(path, reporter))))
+        } else {
+            None
+        };
+        let mut checksum = None;
+
+        // Upload a share for each recipient, stop at the first failure
+        let retry = Retry::new(matcher_main);
+        let mut shares = Vec::with_capacity(recipients.len());
+        let mut result = Ok(());
+        for (recipient, params) in recipients.iter().zip(recipient_params) {
+            let downloads = params
+                .as_ref()
+                .and_then(|params| params.download_limit)
+                .map(|downloads| downloads as usize)
+                .unwrap_or(limits.downloads_default);
+            if !matcher_main.quiet() {
+                eprintln!("Uploading for {}...", recipient.name);
+            }
+
+            let checksum_progress: Option<Arc<Mutex<dyn ProgressReporter>>> = checksum_reporter
+                .clone()
+                .map(|reporter| reporter as Arc<Mutex<dyn ProgressReporter>>);
+            let reporter = checksum_progress.as_ref().or(reporter);
+
+            let password = matcher_upload.gen_passphrase();
+            let file = match retry.invoke("upload", || {
+                ApiUpload::new(
+                    api_version,
+                    host.clone(),
+                    path.to_path_buf(),
+                    file_name.clone(),
+                    Some(password.clone()),
+                    params.clone(),
+                )
+                .invoke(client, reporter)
+            }) {
+                Ok(file) => file,
+                Err(err) => {
+                    result = Err(Error::Recipient {
+                        name: recipient.name.clone(),
+                        cause: err,
+                    });
+                    break;
+                }
+            };
+            if let Some(reporter) = checksum_reporter.take() {
+                checksum = Self::take_checksum(&reporter);
+            }
+
+            // Add the share to the history, labelled with the recipient
+            #[cfg(feature = "history")]
+            history_tool::add_meta(
+                matcher_main,
+                file.clone(),
+                FileMeta {
+                    name: file_name.clone().or_else(|| {
+                        path.file_name()
+                            .and_then(|name| name.to_str())
+                            .map(|name| name.to_owned())
+                    }),
+                    sha256: checksum.clone(),
+                    recipient: Some(recipient.name.clone()),
+                    delete_at,
+                    ..FileMeta::default()
+                },
+                false,
+            );
+
+            shares.push((recipient, file, password, downloads));
+        }
+        #[cfg(feature = "history")]
+        {
+            if let Some((_, file, _, _)) = shares.first() {
+                warn_expires_before(file, delete_at);
+            }
+        }
+
+        // Describe each share, to render share messages and copy with
+        let size = path.metadata().ok().map(|metadata| metadata.len());
+        let infos: Vec<ShareInfo> = shares
+            .iter()
+            .map(|(recipient, file, password, downloads)| ShareInfo {
+                url: file.download_url(true),
+                name: file_name.clone().or_else(|| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .map(|name| name.to_owned())
+                }),
+                size,
+                expiry: Some(file.expire_duration()),
+                downloads: Some(*downloads),
+                password: Some(password.clone()),
+                owner: file.owner_token().cloned(),
+                recipient: Some(recipient.name.clone()),
+            })
+            .collect();
+
+        // Report the uploaded shares, or print a share message for each
+        if let Some(template) = message_template {
+            let messages: Vec<String> = infos.iter().map(|info| info.render(template)).collect();
+            if !messages.is_empty() {
+                println!("{}", messages.join("\n\n"));
+            }
+        } else if !matcher_main.quiet() {
+            let mut table = Table::new();
+            table.set_format(FormatBuilder::new().padding(0, 2).build());
+            let mut columns = vec!["RECIPIENT", "LINK", "PASSPHRASE"];
+            if matcher_main.verbose() {
+                columns.push("OWNER TOKEN");
+            }
+            table.add_row(Row::new(columns.into_iter().map(Cell::new).collect()));
+            for (recipient, file, password, _) in &shares {
+                let mut cells = vec![
+                    Cell::new(&recipient.name),
+                    Cell::new(file.download_url(true).as_str()),
+                    Cell::new(password),
+                ];
+                if matcher_main.verbose() {
+                    cells.push(Cell::new(
+                        file.owner_token().map(|t| t.as_str()).unwrap_or("?"),
+                    ));
+                }
+                table.add_row(Row::new(cells));
+            }
+            if !shares.is_empty() {
+                table.printstd();
+                if let Some(entropy) = matcher_upload.passphrase_entropy() {
+                    eprintln!("Passphrase entropy: {}", format_entropy(entropy));
+                }
+            }
+            if let Some(checksum) = &checksum {
+                eprintln!("SHA-256: {}", checksum);
+            }
+        } else {
+            for (recipient, file, password, _) in &shares {
+                println!(
+                    "{} {} {}",
+                    recipient.name,
+                    file.download_url(true),
+                    password
+                );
+            }
+            if let Some(checksum) = &checksum {
+                println!("{}", checksum);
+            }
+        }
+
+        // Copy the selected property or share message of all shares to the user's clipboard
+        #[cfg(feature = "clipboard")]
+        {
+            if let (Some(copy_mode), false) = (matcher_upload.copy(), infos.is_empty()) {
+                let contents: Option<Vec<String>> = infos
+                    .iter()
+                    .map(|info| copy_mode.build(info, info.url.as_str(), None))
+                    .collect();
+                let separator = match copy_mode {
+                    CopyMode::Message(_) => "\n\n",
+                    _ => "\n",
+                };
+                match contents {
+                    Some(contents) => {
+                        if let Err(err) = set_clipboard(contents.join(separator), matcher_main) {
+                            print_error(err.context(format!(
+                                "failed to copy the {} to the clipboard, ignoring",
+                                copy_mode.describe(),
+                            )));
+                        }
                     }
+                    None => print_error_msg(format!(
+                        "no {} to copy to the clipboard, ignoring",
+                        copy_mode.describe(),
+                    )),
                 }
             }
         }
 
-        Ok(())
+        result
+    }
+
+    /// Upload the file at the given `path` split into parts of at most `part_size` bytes.
+    ///
+    /// Each part is uploaded as separate share, followed by a manifest share listing all parts.
+    /// The parts are added to the history so they can be managed. The manifest share is returned,
+    /// along with the checksum of the file computed while splitting.
+    #[allow(clippy::too_many_arguments)]
+    fn upload_split(
+        matcher_main: &MainMatcher,
+        #[allow(unused)] matcher_upload: &UploadMatcher,
+        api_version: Version,
+        host: Url,
+        path: &Path,
+        file_name: Option<String>,
+        password: Option<String>,
+        params: Option<ParamsData>,
+        part_size: u64,
+        client: &Client,
+        reporter: Option<&Arc<Mutex<dyn ProgressReporter>>>,
+    ) -> Result<(RemoteFile, String), Error> {
+        // Determine the name of the original file
+        let name = file_name.unwrap_or_else(|| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.to_owned())
+                .unwrap_or_else(|| String::from("file"))
+        });
+
+        // Split the file into parts in a temporary directory
+        let tmp_dir = TempBuilder::new()
+            .prefix(&format!(".{}-split-", crate_name!()))
+            .tempdir()
+            .map_err(SplitError::TempDir)?;
+        let (parts, checksum) =
+            manifest::split(path, part_size, tmp_dir.path()).map_err(SplitError::Split)?;
+
+        // Get the scheduled deletion time for all parts
+        #[cfg(feature = "history")]
+        let delete_at = matcher_upload.delete_at();
+
+        // Upload each part
+        let retry = Retry::new(matcher_main);
+        let mut manifest_parts = Vec::with_capacity(parts.len());
+        for (i, part) in parts.iter().enumerate() {
+            if !matcher_main.quiet() {
+                eprintln!("Uploading part {} of {}...", i + 1, parts.len());
+            }
+
+            let part_name = format!("{}.part{}", name, i + 1);
+            let file = retry.invoke("part upload", || {
+                ApiUpload::new(
+                    api_version,
+                    host.clone(),
+                    part.path.clone(),
+                    Some(part_name.clone()),
+                    password.clone(),
+                    params.clone(),
+                )
+                .invoke(client, reporter)
+            })?;
+
+            manifest_parts.push(manifest::Part {
+                url: file.download_url(true).into(),
+                size: part.size,
+                sha256: part.sha256.clone(),
+            });
+
+            // Add the part to the history, to allow managing it
+            #[cfg(feature = "history")]
+            history_tool::add_meta(
+                matcher_main,
+                file,
+                FileMeta {
+                    name: Some(part_name),
+                    delete_at,
+                    ..FileMeta::default()
+                },
+                false,
+            );
+        }
+
+        // Build the manifest, and upload it
+        if !matcher_main.quiet() {
+            eprintln!("Uploading manifest...");
+        }
+        let manifest = Manifest::new(
+            name.clone(),
+            parts.iter().map(|part| part.size).sum(),
+            checksum.clone(),
+            manifest_parts,
+        );
+        let manifest_path = tmp_dir.path().join("manifest");
+        manifest
+            .save(&manifest_path)
+            .map_err(SplitError::Manifest)?;
+        let file = retry.invoke("manifest upload", || {
+            ApiUpload::new(
+                api_version,
+                host.clone(),
+                manifest_path.clone(),
+                Some(Manifest::manifest_name(&name)),
+                password.clone(),
+                params.clone(),
+            )
+            .invoke(client, reporter)
+        })?;
+
+        Ok((file, checksum))
+    }
+
+    /// Get the file checksum computed by the given reporter while uploading.
+    ///
+    /// If the checksum couldn't be computed, an error is reported and `None` is returned.
+    fn take_checksum(reporter: &Mutex<ChecksumReporter>) -> Option<String> {
+        match reporter.lock().unwrap().checksum() {
+            Ok(checksum) => Some(checksum),
+            Err(err) => {
+                print_error(err.context("failed to compute file checksum, ignoring"));
+                None
+            }
+        }
     }
 }
 
@@ -600,6 +1227,16 @@ fn shared_dir(paths: Vec<PathBuf>) -> Option<PathBuf> {
     i.map(|i| c[0][i].to_path_buf())
 }
 
+/// Warn if the given file expires before its scheduled deletion time, as it can't be deleted then.
+#[cfg(feature = "history")]
+fn warn_expires_before(file: &RemoteFile, delete_at: Option<DateTime<Utc>>) {
+    if let Some(delete_at) = delete_at {
+        if Utc::now() + file.expire_duration() < delete_at {
+            print_warning("the file expires before its scheduled deletion time");
+        }
+    }
+}
+
 #[derive(Debug, Fail)]
 pub enum Error {
     /// Selecting the API version to use failed.
@@ -616,6 +1253,18 @@ pub enum Error {
     #[fail(display = "")]
     Upload(#[cause] UploadError),
 
+    /// An error occurred while splitting the file into parts.
+    #[fail(display = "failed to split file into parts")]
+    Split(#[cause] SplitError),
+
+    /// An error occurred while uploading the share for a recipient.
+    #[fail(display = "failed to upload the share for recipient '{}'", name)]
+    Recipient {
+        name: String,
+        #[cause]
+        cause: UploadError,
+    },
+
     /// An error occurred while deleting a local file after upload.
     #[fail(display = "failed to delete local file")]
     Delete(#[cause] IoError),
@@ -642,6 +1291,12 @@ impl From<ArchiveError> for Error {
     }
 }
 
+impl From<SplitError> for Error {
+    fn from(err: SplitError) -> Error {
+        Error::Split(err)
+    }
+}
+
 impl From<UploadError> for Error {
     fn from(err: UploadError) -> Error {
         Error::Upload(err)
@@ -671,3 +1326,18 @@ pub enum ArchiveError {
     #[fail(display = "failed to write archive to disk")]
     Write(#[cause] IoError),
 }
+
+#[derive(Debug, Fail)]
+pub enum SplitError {
+    /// An error occurred while creating the temporary directory for parts.
+    #[fail(display = "failed to create temporary directory for parts")]
+    TempDir(#[cause] IoError),
+
+    /// Failed to split the file into parts.
+    #[fail(display = "failed to split file")]
+    Split(#[cause] manifest::Error),
+
+    /// Failed to write the manifest.
+    #[fail(display = "failed to write manifest")]
+    Manifest(#[cause] manifest::Error),
+}
@@ -0,0 +1,311 @@
+use std::process::Command;
+use std::thread::sleep;
+use std::time::Duration;
+
+use chrono::Duration as ChronoDuration;
+use clap::ArgMatches;
+use failure::Fail;
+use ffsend_api::action::exists::{Error as ExistsError, Exists as ApiExists};
+use ffsend_api::action::info::{Error as InfoError, Info as ApiInfo};
+use ffsend_api::client::Client;
+use ffsend_api::file::remote_file::RemoteFile;
+use serde_json::json;
+
+use crate::client::create_config;
+use crate::cmd::matcher::{main::MainMatcher, watch::WatchMatcher, Matcher};
+use crate::error::ActionError;
+#[cfg(feature = "history")]
+use crate::history_tool;
+use crate::util::{ensure_owner_token, highlight, print_error, print_error_msg};
+
+/// A watch downloads action.
+pub struct Watch<'a> {
+    cmd_matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> Watch<'a> {
+    /// Construct a new watch downloads action.
+    pub fn new(cmd_matches: &'a ArgMatches<'a>) -> Self {
+        Self { cmd_matches }
+    }
+
+    /// Invoke the watch downloads action.
+    // TODO: create a trait for this method
+    pub fn invoke(&self) -> Result<(), ActionError> {
+        // Create the command matchers
+        let matcher_main = MainMatcher::with(self.cmd_matches).unwrap();
+        let matcher_watch = WatchMatcher::with(self.cmd_matches).unwrap();
+
+        // Create client
+        let client_config = create_config(&matcher_main);
+        let client = client_config.client(false);
+
+        // Collect the files to watch
+        let mut watched = Self::watched_files(&matcher_main, &matcher_watch)?;
+        if watched.is_empty() {
+            if !matcher_main.quiet() {
+                eprintln!("No files to watch");
+            }
+            return Ok(());
+        }
+        if !matcher_main.quiet() {
+            eprintln!(
+                "Watching {} file(s), use {} to stop",
+                watched.len(),
+                highlight("[CTRL+C]"),
+            );
+        }
+
+        // Poll all watched files until they have expired
+        let interval = Duration::from_secs(matcher_watch.interval());
+        loop {
+            watched.retain_mut(|watched| {
+                match watched.poll(&client) {
+                    Ok(events) => {
+                        for event in &events {
+                            event.emit(watched, &matcher_watch);
+                        }
+
+                        // Stop watching and remove expired files from history
+                        if events.iter().any(|event| matches!(event, Event::Expired)) {
+                            #[cfg(feature = "history")]
+                            history_tool::remove(&matcher_main, &watched.file);
+                            return false;
+                        }
+                    }
+                    Err(err) => print_error(
+                        err.context(format!("failed to poll '{}', ignoring", watched.label)),
+                    ),
+                }
+                true
+            });
+
+            if watched.is_empty() {
+                break;
+            }
+            sleep(interval);
+        }
+
+        if !matcher_main.quiet() {
+            eprintln!("All watched files have expired");
+        }
+
+        Ok(())
+    }
+
+    /// Collect the list of files to watch, from the given URL or all files in history.
+    fn watched_files(
+        matcher_main: &MainMatcher,
+        matcher_watch: &WatchMatcher,
+    ) -> Result<Vec<Watched>, ActionError> {
+        // Watch all files in history
+        #[cfg(feature = "history")]
+        {
+            if matcher_watch.all() {
+                let history = history_tool::load_with_owner_tokens(matcher_main);
+                return Ok(history
+                    .files_sorted()
+                    .into_iter()
+                    .map(|file| Watched::new(file.clone(), history.label(file)))
+                    .collect());
+            }
+        }
+
+        // Parse the remote file based on the share URL, derive the owner token from history
+        let mut file = RemoteFile::parse_url(matcher_watch.url(), matcher_watch.owner())?;
+        #[cfg(feature = "history")]
+        history_tool::derive_file_properties(matcher_main, &mut file);
+        #[cfg(feature = "secret-store")]
+        history_tool::derive_owner_token(matcher_main, &mut file);
+
+        // The owner token is required to watch for downloads
+        if !ensure_owner_token(file.owner_token_mut(), matcher_main, true) {
+            print_error_msg("no owner token, only watching for expiry");
+        }
+
+        #[cfg(feature = "history")]
+        let label = history_tool::load(matcher_main).label(&file);
+        #[cfg(not(feature = "history"))]
+        let label = file.download_url(false).into();
+
+        Ok(vec![Watched::new(file, label)])
+    }
+}
+
+/// A file that is being watched.
+struct Watched {
+    /// The remote file.
+    file: RemoteFile,
+
+    /// The label to show to the user for this file.
+    label: String,
+
+    /// The download count and limit as last seen, if known.
+    downloads: Option<(usize, usize)>,
+}
+
+impl Watched {
+    /// Construct a new watched file.
+    fn new(file: RemoteFile, label: String) -> Self {
+        Self {
+            file,
+            label,
+            downloads: None,
+        }
+    }
+
+    /// Poll the remote host for the current state of this file.
+    ///
+    /// The events since the last poll are returned, such as the file being downloaded or having
+    /// expired. The download count is only known if the owner token is available.
+    fn poll(&mut self, client: &Client) -> Result<Vec<Event>, PollError> {
+        // Check whether the file still exists
+        if !ApiExists::new(&self.file)
+            .invoke(client)
+            .map_err(PollError::Exists)?
+            .exists()
+        {
+            return Ok(self.expired());
+        }
+
+        // Fetch the download count if we have an owner token
+        if !self.file.has_owner_token() {
+            return Ok(vec![]);
+        }
+        let info = match ApiInfo::new(&self.file, None).invoke(client) {
+            Ok(info) => info,
+            Err(InfoError::Expired) => return Ok(self.expired()),
+            Err(err) => return Err(PollError::Info(err)),
+        };
+        self.file
+            .set_expire_duration(ChronoDuration::milliseconds(info.ttl_millis() as i64));
+
+        // Report a download if the count increased since the last poll
+        let downloads = (info.download_count(), info.download_limit());
+        let previous = self.downloads.replace(downloads);
+        match previous {
+            Some((count, _)) if downloads.0 > count => Ok(vec![Event::Download]),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Get the events for this file that is gone from the remote host.
+    ///
+    /// The host removes a file once its download limit is reached, so the last download is only
+    /// noticed as the file being gone. If the last known count was one below the limit, or if the
+    /// file is gone before its expiry time, a download is reported before the file expired.
+    fn expired(&mut self) -> Vec<Event> {
+        let downloaded = match self.downloads {
+            Some((count, limit)) => count + 1 == limit || !self.file.has_expired(),
+            None => !self.file.has_expired(),
+        };
+        if !downloaded {
+            return vec![Event::Expired];
+        }
+
+        if let Some((_, limit)) = self.downloads {
+            self.downloads = Some((limit, limit));
+        }
+        vec![Event::Download, Event::Expired]
+    }
+}
+
+/// An event for a watched file.
+enum Event {
+    /// The file was downloaded.
+    Download,
+
+    /// The file has expired, or was deleted.
+    Expired,
+}
+
+impl Event {
+    /// Get the event name.
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Download => "download",
+            Event::Expired => "expired",
+        }
+    }
+
+    /// Emit this event for the given watched file.
+    ///
+    /// The event is printed to stdout, and the command hook is run if specified.
+    fn emit(&self, watched: &Watched, matcher_watch: &WatchMatcher) {
+        let url = watched.file.download_url(false);
+        let (downloads, limit) = match watched.downloads {
+            Some((downloads, limit)) => (Some(downloads), Some(limit)),
+            None => (None, None),
+        };
+
+        // Print the event
+        if matcher_watch.json() {
+            println!(
+                "{}",
+                json!({
+                    "event": self.name(),
+                    "id": watched.file.id(),
+                    "url": url.as_str(),
+                    "label": watched.label,
+                    "downloads": downloads,
+                    "download_limit": limit,
+                })
+            );
+        } else {
+            match (self, downloads, limit) {
+                (Event::Download, Some(downloads), Some(limit)) => println!(
+                    "{}: {} ({} of {})",
+                    self.name(),
+                    watched.label,
+                    downloads,
+                    limit
+                ),
+                _ => println!("{}: {}", self.name(), watched.label),
+            }
+        }
+
+        // Run the command hook
+        if let Some(exec) = matcher_watch.exec() {
+            #[cfg(not(windows))]
+            let mut cmd = Command::new("sh");
+            #[cfg(not(windows))]
+            cmd.arg("-c").arg(exec);
+            #[cfg(windows)]
+            let mut cmd = Command::new("cmd");
+            #[cfg(windows)]
+            cmd.arg("/C").arg(exec);
+
+            cmd.env("FFSEND_EVENT", self.name())
+                .env("FFSEND_ID", watched.file.id())
+                .env("FFSEND_URL", url.as_str())
+                .env("FFSEND_LABEL", &watched.label)
+                .env(
+                    "FFSEND_DOWNLOADS",
+                    downloads.map(|d| d.to_string()).unwrap_or_default(),
+                )
+                .env(
+                    "FFSEND_DOWNLOAD_LIMIT",
+                    limit.map(|l| l.to_string()).unwrap_or_default(),
+                );
+            match cmd.status() {
+                Ok(status) if !status.success() => print_error_msg(format!(
+                    "watch command exited with status code {}, ignoring",
+                    status.code().unwrap_or(0),
+                )),
+                Ok(_) => {}
+                Err(err) => print_error(err.context("failed to run watch command, ignoring")),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Fail)]
+pub enum PollError {
+    /// An error occurred while checking if the file exists.
+    #[fail(display = "failed to check whether the file exists")]
+    Exists(#[cause] ExistsError),
+
+    /// An error occurred while fetching the file information.
+    #[fail(display = "failed to fetch file info")]
+    Info(#[cause] InfoError),
+}
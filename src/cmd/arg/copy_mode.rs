@@ -0,0 +1,50 @@
+use clap::{Arg, ArgMatches};
+
+use super::{ArgFormatMessage, CmdArg, CmdArgFlag, CmdArgOption};
+use crate::message::CopyMode;
+
+/// The copy mode argument.
+pub struct ArgCopyMode {}
+
+impl ArgCopyMode {
+    /// Check whether the copy mode was given on the command line, which implies copying.
+    ///
+    /// A copy mode set through the environment only selects what to copy.
+    pub fn given(matches: &ArgMatches) -> bool {
+        matches.occurrences_of(Self::name()) > 0
+    }
+}
+
+impl CmdArg for ArgCopyMode {
+    fn name() -> &'static str {
+        "copy-mode"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("copy-mode")
+            .long("copy-mode")
+            .value_name("MODE")
+            .env("FFSEND_COPY_MODE")
+            .possible_values(CopyMode::names())
+            .case_insensitive(true)
+            .help("What to copy to your clipboard")
+            .long_help(
+                "What to copy to your clipboard: the share link (url), a download command \
+                 (cmd), the share link and password (url-password), the password, the owner \
+                 token (owner) or a share message rendered from the --format-message template \
+                 (message). Implies copying when given.",
+            )
+    }
+}
+
+impl CmdArgFlag for ArgCopyMode {}
+
+impl<'a> CmdArgOption<'a> for ArgCopyMode {
+    type Value = Option<CopyMode>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(|mode| {
+            CopyMode::from_name(mode, ArgFormatMessage::value(matches)).expect("invalid copy mode")
+        })
+    }
+}
@@ -1,37 +1,82 @@
+use std::convert::TryFrom;
+
 use clap::{Arg, ArgMatches};
-use ffsend_api::api::Version as ApiVersion;
-use ffsend_api::config::downloads_max;
 
 use super::{CmdArg, CmdArgFlag, CmdArgOption};
 use crate::cmd::matcher::MainMatcher;
-use crate::util::{highlight, prompt_yes, quit};
+use crate::limits::Limits;
+use crate::util::{highlight, prompt_yes, quit, quit_error_msg, ErrorHintsBuilder};
 
 /// The download limit argument.
 pub struct ArgDownloadLimit {}
 
 impl ArgDownloadLimit {
+    /// Build the argument for generating shell completions, offering the default download limits.
+    ///
+    /// The offered values are enforced by the argument parser, so this must not be used to parse
+    /// arguments with, as hosts may allow other limits.
+    pub fn build_completions<'b, 'c>() -> Arg<'b, 'c> {
+        Self::build().possible_values(&Limits::completion_downloads())
+    }
+
     pub fn value_checked<'a>(
         matches: &ArgMatches<'a>,
         main_matcher: &MainMatcher,
-        api_version: ApiVersion,
+        limits: &Limits,
         auth: bool,
-    ) -> Option<usize> {
-        // Get the download value
-        let mut downloads = Self::value(matches)?;
+    ) -> Option<u8> {
+        Self::value(matches).map(|downloads| Self::check(downloads, main_matcher, limits, auth))
+    }
 
+    /// Check the given download limit against the host limits.
+    ///
+    /// If the limit isn't allowed, the user is prompted to use the closest allowed limit instead.
+    /// The program quits if the user cancels, or if the limit doesn't fit the API, even when
+    /// forcing.
+    pub fn check(downloads: usize, main_matcher: &MainMatcher, limits: &Limits, auth: bool) -> u8 {
+        let downloads = Self::check_allowed(downloads, main_matcher, limits, auth);
+        match u8::try_from(downloads) {
+            Ok(downloads) => downloads,
+            Err(_) => quit_error_msg(
+                format!(
+                    "the download limit {} is too high, the maximum is {}",
+                    downloads,
+                    u8::MAX
+                ),
+                ErrorHintsBuilder::default()
+                    .force(false)
+                    .verbose(false)
+                    .build()
+                    .unwrap(),
+            ),
+        }
+    }
+
+    /// Check the given download limit against the allowed limits of the host, see `check`.
+    fn check_allowed(
+        downloads: usize,
+        main_matcher: &MainMatcher,
+        limits: &Limits,
+        auth: bool,
+    ) -> usize {
         // Get number of allowed downloads, return if allowed or when forcing
-        let allowed = downloads_max(api_version, auth);
+        let allowed = &limits.downloads;
         if allowed.contains(&downloads) || main_matcher.force() {
-            return Some(downloads);
+            return downloads;
         }
 
         // Prompt the user the specified downloads limit is invalid
-        let allowed_str = allowed
-            .iter()
-            .map(|value| format!("{}", value))
-            .collect::<Vec<_>>()
-            .join(", ");
-        eprintln!("The downloads limit must be one of: {}", allowed_str);
+        if limits.from_host {
+            eprintln!(
+                "The downloads limit on this host must be one of: {}",
+                limits.downloads_list()
+            );
+        } else {
+            eprintln!(
+                "The downloads limit must be one of: {}",
+                limits.downloads_list()
+            );
+        }
         if auth {
             eprintln!("Use '{}' to force", highlight("--force"));
         } else {
@@ -50,9 +95,7 @@ impl ArgDownloadLimit {
         ) {
             quit();
         }
-        downloads = closest;
-
-        Some(downloads)
+        closest
     }
 }
 
@@ -1,11 +1,10 @@
 use chrono::Duration;
 use clap::{Arg, ArgMatches};
 use failure::Fail;
-use ffsend_api::api::Version as ApiVersion;
-use ffsend_api::config::expiry_max;
 
 use super::{CmdArg, CmdArgFlag, CmdArgOption};
 use crate::cmd::matcher::MainMatcher;
+use crate::limits::Limits;
 use crate::util::{
     format_duration, highlight, parse_duration, prompt_yes, quit, quit_error, ErrorHints,
 };
@@ -14,17 +13,25 @@ use crate::util::{
 pub struct ArgExpiryTime {}
 
 impl ArgExpiryTime {
+    /// Build the argument for generating shell completions, offering the default expiry times.
+    ///
+    /// The offered values are enforced by the argument parser, so this must not be used to parse
+    /// arguments with, as hosts may allow other times.
+    pub fn build_completions<'b, 'c>() -> Arg<'b, 'c> {
+        Self::build().possible_values(&Limits::completion_expiry_times())
+    }
+
     pub fn value_checked<'a>(
         matches: &ArgMatches<'a>,
         main_matcher: &MainMatcher,
-        api_version: ApiVersion,
+        limits: &Limits,
         auth: bool,
     ) -> Option<usize> {
         // Get the expiry time value
         let mut expiry = Self::value(matches)?;
 
         // Get expiry time, return if allowed or when forcing
-        let max = *expiry_max(api_version, auth).into_iter().max().unwrap();
+        let max = limits.expiry_max;
         if expiry <= max || main_matcher.force() {
             return Some(expiry);
         }
@@ -33,10 +40,20 @@ impl ArgExpiryTime {
         let format_secs = |secs: usize| format_duration(Duration::seconds(secs as i64));
 
         // Prompt the user the specified expiry time is invalid
-        eprintln!(
-            "The expiry time must equal to or less than: {}",
-            format_secs(max),
-        );
+        if limits.from_host {
+            eprintln!(
+                "The expiry time on this host must equal to or less than: {}",
+                format_secs(max),
+            );
+        } else {
+            eprintln!(
+                "The expiry time must equal to or less than: {}",
+                format_secs(max),
+            );
+        }
+        if !limits.expiry_times.is_empty() {
+            eprintln!("Suggested expiry times: {}", limits.expiry_list());
+        }
         if auth {
             eprintln!("Use '{}' to force", highlight("--force"));
         } else {
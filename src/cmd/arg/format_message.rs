@@ -0,0 +1,48 @@
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+use crate::message;
+
+/// The message template argument.
+pub struct ArgFormatMessage {}
+
+impl ArgFormatMessage {
+    /// Check whether a message template was given on the command line.
+    ///
+    /// A template set through the environment is only used where a message is rendered anyway.
+    pub fn given(matches: &ArgMatches) -> bool {
+        matches.occurrences_of(Self::name()) > 0
+    }
+}
+
+impl CmdArg for ArgFormatMessage {
+    fn name() -> &'static str {
+        "format-message"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("format-message")
+            .long("format-message")
+            .alias("message")
+            .value_name("TEMPLATE")
+            .env("FFSEND_FORMAT_MESSAGE")
+            .help("Print a share message rendered from a template")
+            .long_help(
+                "Render a share message from the given template. The placeholders {url}, {name}, {size}, {expiry}, {downloads}, \
+                 {password}, {owner} and {recipient} are replaced with properties of the file, \
+                 \\n is replaced with a newline. A template name may be given instead, such as \
+                 plain, markdown, slack, or one configured in messages.toml in the \
+                 configuration directory.",
+            )
+    }
+}
+
+impl CmdArgFlag for ArgFormatMessage {}
+
+impl<'a> CmdArgOption<'a> for ArgFormatMessage {
+    type Value = Option<String>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(message::template)
+    }
+}
@@ -0,0 +1,22 @@
+use clap::Arg;
+
+use super::{CmdArg, CmdArgFlag};
+
+/// The from clipboard argument.
+pub struct ArgFromClipboard {}
+
+impl CmdArg for ArgFromClipboard {
+    fn name() -> &'static str {
+        "from-clipboard"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("from-clipboard")
+            .long("from-clipboard")
+            .alias("paste")
+            .conflicts_with("URL")
+            .help("Read the share URL from the clipboard")
+    }
+}
+
+impl CmdArgFlag for ArgFromClipboard {}
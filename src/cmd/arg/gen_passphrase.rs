@@ -1,16 +1,102 @@
-use chbs;
-use clap::Arg;
+use clap::{Arg, ArgMatches};
+use failure::Fail;
 
-use super::{CmdArg, CmdArgFlag};
+use super::{
+    ArgPassphraseCase, ArgPassphraseSeparator, ArgPassphraseWordlist, ArgPassphraseWords,
+    ArgPassword, ArgPasswordCharset, ArgPasswordLength, CmdArg, CmdArgFlag, CmdArgOption,
+};
+use crate::passphrase::{Case, Policy, LENGTH_DEFAULT, SEPARATOR_DEFAULT, WORDS_DEFAULT};
+use crate::util::{quit_error, quit_error_msg, ErrorHintsBuilder};
 
 /// The passphrase generation argument.
 pub struct ArgGenPassphrase {}
 
 impl ArgGenPassphrase {
-    /// Generate a cryptographically secure passphrase that is easily
-    /// remembered using diceware.
-    pub fn gen_passphrase() -> String {
-        chbs::passphrase()
+    /// Check whether to generate a passphrase.
+    ///
+    /// Giving a passphrase policy argument on the command line implies generating a passphrase,
+    /// a policy set through the environment only configures generated passphrases. If a policy
+    /// argument is combined with a password, the program quits with an error message.
+    pub fn requested<'a>(matches: &ArgMatches<'a>) -> bool {
+        let policy_args = [
+            ArgPassphraseWords::name(),
+            ArgPassphraseSeparator::name(),
+            ArgPassphraseCase::name(),
+            ArgPassphraseWordlist::name(),
+            ArgPasswordLength::name(),
+            ArgPasswordCharset::name(),
+        ];
+        let policy_arg = policy_args
+            .iter()
+            .find(|name| matches.occurrences_of(name) > 0);
+
+        match policy_arg {
+            Some(name) if ArgPassword::is_present(matches) => quit_error_msg(
+                format!(
+                    "the argument '--{}' cannot be used with '--{}'",
+                    name,
+                    ArgPassword::name()
+                ),
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            ),
+            Some(_) => true,
+            None => Self::is_present(matches),
+        }
+    }
+
+    /// Get the policy to generate passphrases with, from the passphrase policy arguments.
+    ///
+    /// Random character passwords are generated if a length or character set is given,
+    /// passphrases of words are generated otherwise.
+    pub fn policy<'a>(matches: &ArgMatches<'a>) -> Policy {
+        let length = ArgPasswordLength::value(matches);
+        let charset = ArgPasswordCharset::value(matches);
+        if length.is_some() || charset.is_some() {
+            return Policy::Chars {
+                length: length.unwrap_or(LENGTH_DEFAULT),
+                charset: charset.unwrap_or_default(),
+            };
+        }
+
+        Policy::Words {
+            words: ArgPassphraseWords::value(matches).unwrap_or(WORDS_DEFAULT),
+            separator: ArgPassphraseSeparator::value(matches)
+                .unwrap_or(SEPARATOR_DEFAULT)
+                .into(),
+            case: ArgPassphraseCase::value(matches).unwrap_or(Case::Mixed),
+            wordlist: ArgPassphraseWordlist::value(matches),
+        }
+    }
+
+    /// Generate a cryptographically secure passphrase based on the selected policy.
+    ///
+    /// By default this is a passphrase that is easily remembered using diceware.
+    /// If the passphrase could not be generated, the program will quit with an error message.
+    pub fn gen_passphrase<'a>(matches: &ArgMatches<'a>) -> String {
+        match Self::policy(matches).generate() {
+            Ok(passphrase) => passphrase,
+            Err(err) => quit_error(
+                err.context("failed to generate passphrase"),
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            ),
+        }
+    }
+
+    /// Estimate the entropy of passphrases generated by the selected policy, in bits.
+    pub fn entropy<'a>(matches: &ArgMatches<'a>) -> Option<f64> {
+        Self::policy(matches).entropy().ok()
+    }
+
+    /// Build the arguments to configure the passphrase generation policy.
+    pub fn build_policy<'b, 'c>() -> Vec<Arg<'b, 'c>> {
+        vec![
+            ArgPassphraseWords::build(),
+            ArgPassphraseSeparator::build(),
+            ArgPassphraseCase::build(),
+            ArgPassphraseWordlist::build(),
+            ArgPasswordLength::build(),
+            ArgPasswordCharset::build(),
+        ]
     }
 }
 
@@ -28,6 +114,10 @@ impl CmdArg for ArgGenPassphrase {
             .short("P")
             .conflicts_with("password")
             .help("Protect the file with a generated passphrase")
+            .long_help(
+                "Protect the file with a generated passphrase. Implied when a passphrase policy \
+                 such as --password-length is given.",
+            )
     }
 }
 
@@ -1,22 +1,58 @@
 pub mod api;
 pub mod basic_auth;
+#[cfg(feature = "clipboard")]
+pub mod copy_mode;
 pub mod download_limit;
 pub mod expiry_time;
+pub mod format_message;
+#[cfg(feature = "clipboard")]
+pub mod from_clipboard;
 pub mod gen_passphrase;
 pub mod host;
 pub mod owner;
+pub mod passphrase_case;
+pub mod passphrase_separator;
+pub mod passphrase_wordlist;
+pub mod passphrase_words;
 pub mod password;
+pub mod password_charset;
+pub mod password_length;
+pub mod password_policy;
+#[cfg(feature = "qrcode")]
+pub mod qrcode_ec;
+#[cfg(feature = "qrcode")]
+pub mod qrcode_format;
+#[cfg(feature = "qrcode")]
+pub mod qrcode_output;
 pub mod url;
 
 // Re-export to arg module
 pub use self::api::ArgApi;
 pub use self::basic_auth::ArgBasicAuth;
+#[cfg(feature = "clipboard")]
+pub use self::copy_mode::ArgCopyMode;
 pub use self::download_limit::ArgDownloadLimit;
 pub use self::expiry_time::ArgExpiryTime;
+pub use self::format_message::ArgFormatMessage;
+#[cfg(feature = "clipboard")]
+pub use self::from_clipboard::ArgFromClipboard;
 pub use self::gen_passphrase::ArgGenPassphrase;
 pub use self::host::ArgHost;
 pub use self::owner::ArgOwner;
+pub use self::passphrase_case::ArgPassphraseCase;
+pub use self::passphrase_separator::ArgPassphraseSeparator;
+pub use self::passphrase_wordlist::ArgPassphraseWordlist;
+pub use self::passphrase_words::ArgPassphraseWords;
 pub use self::password::ArgPassword;
+pub use self::password_charset::ArgPasswordCharset;
+pub use self::password_length::ArgPasswordLength;
+pub use self::password_policy::ArgPasswordPolicy;
+#[cfg(feature = "qrcode")]
+pub use self::qrcode_ec::ArgQrEcLevel;
+#[cfg(feature = "qrcode")]
+pub use self::qrcode_format::ArgQrFormat;
+#[cfg(feature = "qrcode")]
+pub use self::qrcode_output::ArgQrOutput;
 pub use self::url::ArgUrl;
 
 use clap::{Arg, ArgMatches};
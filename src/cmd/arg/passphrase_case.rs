@@ -0,0 +1,33 @@
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+use crate::passphrase::Case;
+
+/// The passphrase capitalization argument.
+pub struct ArgPassphraseCase {}
+
+impl CmdArg for ArgPassphraseCase {
+    fn name() -> &'static str {
+        "passphrase-case"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("passphrase-case")
+            .long("passphrase-case")
+            .value_name("CASE")
+            .env("FFSEND_PASSPHRASE_CASE")
+            .possible_values(Case::names())
+            .case_insensitive(true)
+            .help("Capitalization of words in generated passphrases")
+    }
+}
+
+impl CmdArgFlag for ArgPassphraseCase {}
+
+impl<'a> CmdArgOption<'a> for ArgPassphraseCase {
+    type Value = Option<Case>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(|case| Case::from_name(case).expect("invalid case"))
+    }
+}
@@ -0,0 +1,31 @@
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+
+/// The passphrase word separator argument.
+pub struct ArgPassphraseSeparator {}
+
+impl CmdArg for ArgPassphraseSeparator {
+    fn name() -> &'static str {
+        "passphrase-separator"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("passphrase-separator")
+            .long("passphrase-separator")
+            .value_name("SEPARATOR")
+            .env("FFSEND_PASSPHRASE_SEPARATOR")
+            .empty_values(true)
+            .help("Separator between words in generated passphrases")
+    }
+}
+
+impl CmdArgFlag for ArgPassphraseSeparator {}
+
+impl<'a> CmdArgOption<'a> for ArgPassphraseSeparator {
+    type Value = Option<&'a str>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches)
+    }
+}
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+
+/// The passphrase wordlist argument.
+pub struct ArgPassphraseWordlist {}
+
+impl CmdArg for ArgPassphraseWordlist {
+    fn name() -> &'static str {
+        "passphrase-wordlist"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("passphrase-wordlist")
+            .long("passphrase-wordlist")
+            .value_name("FILE")
+            .env("FFSEND_PASSPHRASE_WORDLIST")
+            .help("Wordlist file to generate passphrases from, one word per line")
+    }
+}
+
+impl CmdArgFlag for ArgPassphraseWordlist {}
+
+impl<'a> CmdArgOption<'a> for ArgPassphraseWordlist {
+    type Value = Option<PathBuf>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(PathBuf::from)
+    }
+}
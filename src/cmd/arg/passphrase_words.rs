@@ -0,0 +1,34 @@
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+
+/// The passphrase word count argument.
+pub struct ArgPassphraseWords {}
+
+impl CmdArg for ArgPassphraseWords {
+    fn name() -> &'static str {
+        "passphrase-words"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("passphrase-words")
+            .long("passphrase-words")
+            .value_name("COUNT")
+            .env("FFSEND_PASSPHRASE_WORDS")
+            .validator(|arg| match arg.parse::<usize>() {
+                Ok(count) if count > 0 => Ok(()),
+                _ => Err(String::from("Word count must be a positive number")),
+            })
+            .help("Number of words in generated passphrases")
+    }
+}
+
+impl CmdArgFlag for ArgPassphraseWords {}
+
+impl<'a> CmdArgOption<'a> for ArgPassphraseWords {
+    type Value = Option<usize>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(|count| count.parse().expect("invalid word count"))
+    }
+}
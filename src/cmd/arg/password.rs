@@ -29,14 +29,14 @@ impl<'a> CmdArgOption<'a> for ArgPassword {
     type Value = Option<String>;
 
     fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
-        // The password flag must be present
-        if !Self::is_present(matches) {
-            return None;
-        }
-
         // Create a main matcher
         let matcher_main = MainMatcher::with(matches).unwrap();
 
+        // The password flag or a password source must be given
+        if !Self::is_present(matches) && !matcher_main.password_source_given() {
+            return None;
+        }
+
         // Get the password argument value, or prompt
         let password = match Self::value_raw(matches) {
             Some(password) => password.into(),
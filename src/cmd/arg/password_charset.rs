@@ -0,0 +1,41 @@
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+use crate::passphrase::Charset;
+
+/// The generated password character set argument.
+pub struct ArgPasswordCharset {}
+
+impl CmdArg for ArgPasswordCharset {
+    fn name() -> &'static str {
+        "password-charset"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("password-charset")
+            .long("password-charset")
+            .value_name("CHARSET")
+            .env("FFSEND_PASSWORD_CHARSET")
+            .validator(|arg| {
+                Charset::parse(&arg).map(|_| ()).ok_or_else(|| {
+                    String::from("Character set must contain at least two distinct characters")
+                })
+            })
+            .help("Generate passwords of random characters from CHARSET instead of words")
+            .long_help(
+                "Generate passwords of random characters from CHARSET instead of words. Use \
+                 'alphanumeric', 'alphabetic', 'numeric', 'hex' or 'symbols', or give the \
+                 characters to use literally.",
+            )
+    }
+}
+
+impl CmdArgFlag for ArgPasswordCharset {}
+
+impl<'a> CmdArgOption<'a> for ArgPasswordCharset {
+    type Value = Option<Charset>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(|charset| Charset::parse(charset).expect("invalid charset"))
+    }
+}
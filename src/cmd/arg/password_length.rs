@@ -0,0 +1,34 @@
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+
+/// The generated password length argument.
+pub struct ArgPasswordLength {}
+
+impl CmdArg for ArgPasswordLength {
+    fn name() -> &'static str {
+        "password-length"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("password-length")
+            .long("password-length")
+            .value_name("LENGTH")
+            .env("FFSEND_PASSWORD_LENGTH")
+            .validator(|arg| match arg.parse::<usize>() {
+                Ok(length) if length > 0 => Ok(()),
+                _ => Err(String::from("Password length must be a positive number")),
+            })
+            .help("Generate passwords of LENGTH random characters instead of words")
+    }
+}
+
+impl CmdArgFlag for ArgPasswordLength {}
+
+impl<'a> CmdArgOption<'a> for ArgPasswordLength {
+    type Value = Option<usize>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(|length| length.parse().expect("invalid password length"))
+    }
+}
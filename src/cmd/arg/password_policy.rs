@@ -0,0 +1,33 @@
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+use crate::password_policy::Mode;
+
+/// The password policy mode argument.
+pub struct ArgPasswordPolicy {}
+
+impl CmdArg for ArgPasswordPolicy {
+    fn name() -> &'static str {
+        "password-policy"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("password-policy")
+            .long("password-policy")
+            .value_name("MODE")
+            .env("FFSEND_PASSWORD_POLICY")
+            .possible_values(Mode::names())
+            .case_insensitive(true)
+            .help("How to apply the password strength policy to chosen passwords")
+    }
+}
+
+impl CmdArgFlag for ArgPasswordPolicy {}
+
+impl<'a> CmdArgOption<'a> for ArgPasswordPolicy {
+    type Value = Option<Mode>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(|mode| Mode::from_name(mode).expect("invalid mode"))
+    }
+}
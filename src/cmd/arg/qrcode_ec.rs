@@ -0,0 +1,37 @@
+use clap::{Arg, ArgMatches};
+use qr::EcLevel;
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+use crate::qrcode::ec_level_from_name;
+
+/// The QR code error correction level argument.
+pub struct ArgQrEcLevel {}
+
+impl CmdArg for ArgQrEcLevel {
+    fn name() -> &'static str {
+        "qrcode-ec"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("qrcode-ec")
+            .long("qrcode-ec")
+            .alias("qr-ec")
+            .alias("qrcode-error-correction")
+            .value_name("LEVEL")
+            .possible_values(&["L", "M", "Q", "H"])
+            .case_insensitive(true)
+            .help("QR code error correction level, from low to high")
+    }
+}
+
+impl CmdArgFlag for ArgQrEcLevel {}
+
+impl<'a> CmdArgOption<'a> for ArgQrEcLevel {
+    type Value = EcLevel;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches)
+            .map(|level| ec_level_from_name(level).expect("invalid error correction level"))
+            .unwrap_or(EcLevel::M)
+    }
+}
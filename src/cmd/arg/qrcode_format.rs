@@ -0,0 +1,33 @@
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+use crate::qrcode::Format;
+
+/// The QR code format argument.
+pub struct ArgQrFormat {}
+
+impl CmdArg for ArgQrFormat {
+    fn name() -> &'static str {
+        "qrcode-format"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("qrcode-format")
+            .long("qrcode-format")
+            .alias("qr-format")
+            .value_name("FORMAT")
+            .possible_values(Format::names())
+            .case_insensitive(true)
+            .help("QR code format, guessed from the output file if not set")
+    }
+}
+
+impl CmdArgFlag for ArgQrFormat {}
+
+impl<'a> CmdArgOption<'a> for ArgQrFormat {
+    type Value = Option<Format>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(|format| Format::from_name(format).expect("invalid format"))
+    }
+}
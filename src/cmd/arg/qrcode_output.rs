@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use clap::{Arg, ArgMatches};
+
+use super::{CmdArg, CmdArgFlag, CmdArgOption};
+
+/// The QR code output file argument.
+pub struct ArgQrOutput {}
+
+impl CmdArg for ArgQrOutput {
+    fn name() -> &'static str {
+        "qrcode-output"
+    }
+
+    fn build<'b, 'c>() -> Arg<'b, 'c> {
+        Arg::with_name("qrcode-output")
+            .long("qrcode-output")
+            .alias("qr-output")
+            .value_name("FILE")
+            .help("Write the QR code to a file, such as a PNG or SVG image")
+    }
+}
+
+impl CmdArgFlag for ArgQrOutput {}
+
+impl<'a> CmdArgOption<'a> for ArgQrOutput {
+    type Value = Option<PathBuf>;
+
+    fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        Self::value_raw(matches).map(PathBuf::from)
+    }
+}
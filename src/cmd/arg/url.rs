@@ -1,10 +1,20 @@
 use clap::{Arg, ArgMatches};
 use failure::Fail;
+#[cfg(feature = "clipboard")]
+use ffsend_api::file::remote_file::RemoteFile;
 use ffsend_api::url::Url;
 
+#[cfg(feature = "clipboard")]
+use super::{ArgFromClipboard, CmdArgFlag};
 use super::{CmdArg, CmdArgOption};
+#[cfg(any(feature = "clipboard", feature = "history"))]
+use crate::cmd::matcher::{MainMatcher, Matcher};
+#[cfg(feature = "history")]
+use crate::history_tool;
 use crate::host::parse_host;
 use crate::util::{quit_error, ErrorHints};
+#[cfg(feature = "clipboard")]
+use crate::util::{quit_error_msg, ErrorHintsBuilder};
 
 /// The URL argument.
 pub struct ArgUrl {}
@@ -15,10 +25,39 @@ impl CmdArg for ArgUrl {
     }
 
     fn build<'b, 'c>() -> Arg<'b, 'c> {
-        Arg::with_name("URL")
-            .required(true)
-            .multiple(false)
-            .help("The share URL")
+        let arg = Arg::with_name("URL").required(true).multiple(false);
+
+        #[cfg(feature = "history")]
+        let arg = arg
+            .help("The share URL, or history #index, alias or name")
+            .long_help(
+                "The share URL, or a file from history by:\n\
+                 #3, 3: index as listed by the history command\n\
+                 ALIAS: alias set with 'history --alias'\n\
+                 NAME: name of the file\
+                 ",
+            );
+        #[cfg(not(feature = "history"))]
+        let arg = arg.help("The share URL");
+
+        // The URL may be read from the clipboard if not given
+        #[cfg(feature = "clipboard")]
+        let arg = arg.required(false);
+
+        arg
+    }
+}
+
+impl ArgUrl {
+    /// Build the argument, not requiring it if any argument with the given `names` is present.
+    pub fn build_required_unless<'b, 'c>(names: &[&'b str]) -> Arg<'b, 'c> {
+        let arg = Self::build().required_unless_one(names);
+
+        // The URL may be read from the clipboard if not given
+        #[cfg(feature = "clipboard")]
+        let arg = arg.required(false);
+
+        arg
     }
 }
 
@@ -26,9 +65,27 @@ impl<'a> CmdArgOption<'a> for ArgUrl {
     type Value = Url;
 
     fn value<'b: 'a>(matches: &'a ArgMatches<'b>) -> Self::Value {
+        // Read the URL from the clipboard if requested, or if none is given
+        #[cfg(feature = "clipboard")]
+        {
+            let explicit = ArgFromClipboard::is_present(matches);
+            if explicit || Self::value_raw(matches).is_none() {
+                return from_clipboard(matches, explicit);
+            }
+        }
+
         // Get the URL
         let url = Self::value_raw(matches).expect("missing URL");
 
+        // Resolve the URL from history if this isn't an URL
+        #[cfg(feature = "history")]
+        {
+            if !is_url(url) {
+                let matcher_main = MainMatcher::with(matches).unwrap();
+                return history_tool::resolve_url(&matcher_main, url);
+            }
+        }
+
         // Parse the URL
         match parse_host(&url) {
             Ok(url) => url,
@@ -39,3 +96,58 @@ impl<'a> CmdArgOption<'a> for ArgUrl {
         }
     }
 }
+
+/// Check whether the given URL argument looks like an URL, rather than a history reference.
+#[cfg(feature = "history")]
+fn is_url(arg: &str) -> bool {
+    arg.contains("://")
+}
+
+/// Read the share URL from the clipboard.
+///
+/// If the URL is read from the clipboard because no URL was given, the clipboard must contain a
+/// valid share URL. If reading from the clipboard was `explicit`ly requested, any URL is accepted
+/// so shortened share URLs can be used as well.
+/// The program will quit with an error message if no URL could be obtained.
+#[cfg(feature = "clipboard")]
+fn from_clipboard(matches: &ArgMatches, explicit: bool) -> Url {
+    let matcher_main = MainMatcher::with(matches).unwrap();
+
+    // Read the clipboard contents
+    let content = match matcher_main.clipboard().get() {
+        Ok(content) => content,
+        Err(err) if explicit => quit_error(
+            err.context("failed to read share URL from clipboard"),
+            ErrorHints::default(),
+        ),
+        Err(err) => quit_error(
+            err.context("no share URL given, and failed to read it from the clipboard"),
+            ErrorHints::default(),
+        ),
+    };
+
+    // Parse the URL, it must be a share URL if not explicitly reading the clipboard
+    let url = parse_host(content.trim())
+        .ok()
+        .filter(|url| explicit || RemoteFile::parse_url(url.clone(), None).is_ok());
+    let url = match url {
+        Some(url) => url,
+        None if explicit => quit_error_msg(
+            "the clipboard does not contain a valid URL",
+            ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+        ),
+        None => quit_error_msg(
+            "no share URL given, and the clipboard does not contain a share URL",
+            ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+        ),
+    };
+
+    // Show the used URL, without secret
+    if !matcher_main.quiet() {
+        let mut public = url.clone();
+        public.set_fragment(None);
+        eprintln!("Using share URL from clipboard: {}", public);
+    }
+
+    url
+}
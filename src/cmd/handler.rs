@@ -4,26 +4,40 @@ use std::ffi::OsString;
 use clap::{App, AppSettings, Arg, ArgMatches};
 
 use super::arg::{ArgApi, ArgBasicAuth, CmdArg};
-#[cfg(feature = "history")]
-use super::matcher::HistoryMatcher;
+#[cfg(feature = "clipboard")]
+use super::matcher::ClearClipboardMatcher;
+#[cfg(feature = "qrcode")]
+use super::matcher::QrMatcher;
 use super::matcher::{
     DebugMatcher, DeleteMatcher, DownloadMatcher, ExistsMatcher, GenerateMatcher, InfoMatcher,
-    Matcher, ParamsMatcher, PasswordMatcher, UploadMatcher, VersionMatcher,
+    Matcher, ParamsMatcher, PasswordMatcher, UploadMatcher, VersionMatcher, WatchMatcher,
 };
 #[cfg(feature = "history")]
-use super::subcmd::CmdHistory;
+use super::matcher::{HistoryMatcher, QueueMatcher, ReapMatcher, ScheduleDeleteMatcher};
+#[cfg(feature = "clipboard")]
+use super::subcmd::CmdClearClipboard;
+#[cfg(feature = "qrcode")]
+use super::subcmd::CmdQr;
 use super::subcmd::{
     CmdDebug, CmdDelete, CmdDownload, CmdExists, CmdGenerate, CmdInfo, CmdParams, CmdPassword,
-    CmdUpload, CmdVersion,
+    CmdUpload, CmdVersion, CmdWatch,
 };
+#[cfg(feature = "history")]
+use super::subcmd::{CmdHistory, CmdQueue, CmdReap, CmdScheduleDelete};
 #[cfg(feature = "infer-command")]
 use crate::config::INFER_COMMANDS;
-use crate::config::{CLIENT_TIMEOUT, CLIENT_TRANSFER_TIMEOUT};
+use crate::config::{
+    CLIENT_RETRIES, CLIENT_RETRY_BACKOFF, CLIENT_TIMEOUT, CLIENT_TRANSFER_TIMEOUT,
+};
+#[cfg(feature = "secret-store")]
+use crate::secret_store::Backend;
 #[cfg(feature = "history")]
 use crate::util::app_history_file_path_string;
 #[cfg(feature = "infer-command")]
 use crate::util::bin_name;
 use crate::util::parse_duration;
+#[cfg(feature = "clipboard")]
+use crate::util::ClipboardType;
 
 #[cfg(feature = "history")]
 lazy_static! {
@@ -31,6 +45,12 @@ lazy_static! {
     static ref DEFAULT_HISTORY_FILE: String = app_history_file_path_string();
 }
 
+#[cfg(feature = "clipboard")]
+lazy_static! {
+    /// The clipboard backends that may be selected
+    static ref CLIPBOARD_BACKENDS: Vec<&'static str> = ClipboardType::names();
+}
+
 lazy_static! {
     /// The default client timeout in seconds as a string
     static ref DEFAULT_TIMEOUT: String = format!("{}", CLIENT_TIMEOUT);
@@ -38,6 +58,12 @@ lazy_static! {
     /// The default client transfer timeout in seconds as a string
     static ref DEFAULT_TRANSFER_TIMEOUT: String = format!("{}", CLIENT_TRANSFER_TIMEOUT);
 
+    /// The default number of request retries as a string
+    static ref DEFAULT_RETRIES: String = format!("{}", CLIENT_RETRIES);
+
+    /// The default request retry backoff in seconds as a string
+    static ref DEFAULT_RETRY_BACKOFF: String = format!("{}", CLIENT_RETRY_BACKOFF);
+
     /// The about notice in command output.
     static ref APP_ABOUT: String = format!(
         "{}\n\n\
@@ -57,6 +83,18 @@ pub struct Handler<'a> {
 impl<'a: 'b, 'b> Handler<'a> {
     /// Build the application CLI definition.
     pub fn build() -> App<'a, 'b> {
+        Self::build_app(false)
+    }
+
+    /// Build the application CLI definition to generate shell completions from.
+    ///
+    /// This offers the default download limits and expiry times of the API as values.
+    pub fn build_completions() -> App<'a, 'b> {
+        Self::build_app(true)
+    }
+
+    /// Build the application CLI definition, see `build_completions` for `completions`.
+    fn build_app(completions: bool) -> App<'a, 'b> {
         // Build the CLI application definition
         let app = App::new(crate_name!())
             .version(crate_version!())
@@ -136,6 +174,40 @@ impl<'a: 'b, 'b> Handler<'a> {
                         })
                     }),
             )
+            .arg(
+                Arg::with_name("retries")
+                    .long("retries")
+                    .alias("retry")
+                    .global(true)
+                    .value_name("COUNT")
+                    .help("Retries for transient network errors (0 to disable)")
+                    .default_value(&DEFAULT_RETRIES)
+                    .hide_default_value(true)
+                    .env("FFSEND_RETRIES")
+                    .hide_env_values(true)
+                    .validator(|arg| {
+                        arg.parse::<u32>().map(drop).map_err(|_| {
+                            String::from("Retries must be a positive number, or 0 to disable.")
+                        })
+                    }),
+            )
+            .arg(
+                Arg::with_name("retry-backoff")
+                    .long("retry-backoff")
+                    .alias("backoff")
+                    .global(true)
+                    .value_name("SECONDS")
+                    .help("Wait before first retry, doubled for each next retry")
+                    .default_value(&DEFAULT_RETRY_BACKOFF)
+                    .hide_default_value(true)
+                    .env("FFSEND_RETRY_BACKOFF")
+                    .hide_env_values(true)
+                    .validator(|arg| {
+                        parse_duration(&arg).map(drop).map_err(|_| {
+                            String::from("Retry backoff time must be a positive number of seconds.")
+                        })
+                    }),
+            )
             .arg(
                 Arg::with_name("quiet")
                     .long("quiet")
@@ -153,16 +225,48 @@ impl<'a: 'b, 'b> Handler<'a> {
             )
             .arg(ArgApi::build())
             .arg(ArgBasicAuth::build())
+            .arg(
+                Arg::with_name("password-file")
+                    .long("password-file")
+                    .value_name("FILE")
+                    .global(true)
+                    .help("Read passwords from a file, '-' for stdin"),
+            )
+            .arg(
+                Arg::with_name("password-cmd")
+                    .long("password-cmd")
+                    .value_name("COMMAND")
+                    .global(true)
+                    .env("FFSEND_PASSWORD_CMD")
+                    .hide_env_values(true)
+                    .help("Run a command to obtain passwords, such as 'pass show name'"),
+            )
             .subcommand(CmdDebug::build())
             .subcommand(CmdDelete::build())
             .subcommand(CmdDownload::build().display_order(2))
             .subcommand(CmdExists::build())
             .subcommand(CmdGenerate::build())
             .subcommand(CmdInfo::build())
-            .subcommand(CmdParams::build())
+            .subcommand(CmdParams::build(completions))
             .subcommand(CmdPassword::build())
-            .subcommand(CmdUpload::build().display_order(1))
-            .subcommand(CmdVersion::build());
+            .subcommand(CmdUpload::build(completions).display_order(1))
+            .subcommand(CmdVersion::build())
+            .subcommand(CmdWatch::build());
+
+        // On Unix, passwords may be read from a file descriptor
+        #[cfg(unix)]
+        let app = app.arg(
+            Arg::with_name("password-fd")
+                .long("password-fd")
+                .value_name("FD")
+                .global(true)
+                .validator(|arg| {
+                    arg.parse::<i32>()
+                        .map(drop)
+                        .map_err(|_| String::from("File descriptor must be a number"))
+                })
+                .help("Read passwords from a file descriptor"),
+        );
 
         // With history support, a flag for the history file and incognito mode
         #[cfg(feature = "history")]
@@ -189,7 +293,61 @@ impl<'a: 'b, 'b> Handler<'a> {
                     .global(true)
                     .help("Don't update local history for actions"),
             )
-            .subcommand(CmdHistory::build());
+            .subcommand(CmdHistory::build())
+            .subcommand(CmdQueue::build())
+            .subcommand(CmdReap::build())
+            .subcommand(CmdScheduleDelete::build());
+
+        // With secret store support, a flag for where to store owner tokens
+        #[cfg(feature = "secret-store")]
+        let app = app.arg(
+            Arg::with_name("secret-store")
+                .long("secret-store")
+                .value_name("BACKEND")
+                .global(true)
+                .possible_values(Backend::names())
+                .case_insensitive(true)
+                .help("Where to store owner tokens")
+                .env("FFSEND_SECRET_STORE")
+                .hide_env_values(true),
+        );
+
+        // With clipboard support, flags for the clipboard backend and clearing
+        #[cfg(feature = "clipboard")]
+        let app = app
+            .arg(
+                Arg::with_name("clipboard-backend")
+                    .long("clipboard-backend")
+                    .alias("clipboard")
+                    .value_name("NAME")
+                    .global(true)
+                    .possible_values(&CLIPBOARD_BACKENDS)
+                    .case_insensitive(true)
+                    .help("Clipboard backend to use")
+                    .env("FFSEND_CLIPBOARD_BACKEND")
+                    .hide_env_values(true),
+            )
+            .arg(
+                Arg::with_name("clipboard-clear")
+                    .long("clipboard-clear")
+                    .value_name("SECONDS")
+                    .global(true)
+                    .help("Clear the clipboard the given time after copying")
+                    .env("FFSEND_CLIPBOARD_CLEAR")
+                    .hide_env_values(true)
+                    .validator(|arg| {
+                        parse_duration(&arg).map(drop).map_err(|_| {
+                            String::from(
+                                "Clear time must be a positive number of seconds, or 0 to disable.",
+                            )
+                        })
+                    }),
+            )
+            .subcommand(CmdClearClipboard::build());
+
+        // With QR code support, a command to render QR codes
+        #[cfg(feature = "qrcode")]
+        let app = app.subcommand(CmdQr::build());
 
         // Disable color usage if compiled without color support
         #[cfg(feature = "no-color")]
@@ -242,6 +400,12 @@ impl<'a: 'b, 'b> Handler<'a> {
         &self.matches
     }
 
+    /// Get the clear clipboard sub command, if matched.
+    #[cfg(feature = "clipboard")]
+    pub fn clear_clipboard(&'a self) -> Option<ClearClipboardMatcher> {
+        ClearClipboardMatcher::with(&self.matches)
+    }
+
     /// Get the debug sub command, if matched.
     pub fn debug(&'a self) -> Option<DebugMatcher> {
         DebugMatcher::with(&self.matches)
@@ -288,6 +452,30 @@ impl<'a: 'b, 'b> Handler<'a> {
         PasswordMatcher::with(&self.matches)
     }
 
+    /// Get the QR code sub command, if matched.
+    #[cfg(feature = "qrcode")]
+    pub fn qr(&'a self) -> Option<QrMatcher> {
+        QrMatcher::with(&self.matches)
+    }
+
+    /// Get the queue sub command, if matched.
+    #[cfg(feature = "history")]
+    pub fn queue(&'a self) -> Option<QueueMatcher> {
+        QueueMatcher::with(&self.matches)
+    }
+
+    /// Get the reap sub command, if matched.
+    #[cfg(feature = "history")]
+    pub fn reap(&'a self) -> Option<ReapMatcher> {
+        ReapMatcher::with(&self.matches)
+    }
+
+    /// Get the schedule delete sub command, if matched.
+    #[cfg(feature = "history")]
+    pub fn schedule_delete(&'a self) -> Option<ScheduleDeleteMatcher> {
+        ScheduleDeleteMatcher::with(&self.matches)
+    }
+
     /// Get the upload sub command, if matched.
     pub fn upload(&'a self) -> Option<UploadMatcher> {
         UploadMatcher::with(&self.matches)
@@ -297,4 +485,9 @@ impl<'a: 'b, 'b> Handler<'a> {
     pub fn version(&'a self) -> Option<VersionMatcher> {
         VersionMatcher::with(&self.matches)
     }
+
+    /// Get the watch downloads sub command, if matched.
+    pub fn watch(&'a self) -> Option<WatchMatcher> {
+        WatchMatcher::with(&self.matches)
+    }
 }
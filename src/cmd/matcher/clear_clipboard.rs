@@ -0,0 +1,56 @@
+use std::io::stdin;
+
+use clap::ArgMatches;
+use failure::Fail;
+
+use super::Matcher;
+use crate::util::{is_sha256_hex, parse_duration, quit_error, quit_error_msg, ErrorHints};
+
+/// The clear clipboard command matcher.
+pub struct ClearClipboardMatcher<'a> {
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a: 'b, 'b> ClearClipboardMatcher<'a> {
+    /// Get the delay in seconds to wait before clearing the clipboard.
+    pub fn delay(&'a self) -> u64 {
+        self.matches
+            .value_of("delay")
+            .map(|arg| parse_duration(arg).expect("invalid delay value") as u64)
+            .unwrap_or(0)
+    }
+
+    /// Get the SHA-256 hash of the content to clear, if set.
+    ///
+    /// The hash is read from stdin. The program quits with an error if it can't be read or is
+    /// invalid.
+    pub fn content_hash(&'a self) -> Option<String> {
+        if !self.matches.is_present("content-hash-stdin") {
+            return None;
+        }
+
+        let mut hash = String::new();
+        if let Err(err) = stdin().read_line(&mut hash) {
+            quit_error(
+                err.context("failed to read content hash from stdin"),
+                ErrorHints::default(),
+            );
+        }
+        let hash = hash.trim();
+        if !is_sha256_hex(hash) {
+            quit_error_msg(
+                "content hash read from stdin is not a SHA-256 hash",
+                ErrorHints::default(),
+            );
+        }
+        Some(hash.to_owned())
+    }
+}
+
+impl<'a> Matcher<'a> for ClearClipboardMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("clear-clipboard")
+            .map(|matches| ClearClipboardMatcher { matches })
+    }
+}
@@ -24,6 +24,36 @@ impl<'a: 'b, 'b> DeleteMatcher<'a> {
         // TODO: just return a string reference here?
         ArgOwner::value(self.matches).map(|token| token.to_owned())
     }
+
+    /// Check whether to delete all files from history.
+    #[cfg(feature = "history")]
+    pub fn all(&self) -> bool {
+        self.matches.is_present("all")
+    }
+
+    /// Get the recipient to delete the shares for, if specified.
+    #[cfg(feature = "history")]
+    pub fn recipient(&'a self) -> Option<&'a str> {
+        self.matches.value_of("recipient")
+    }
+
+    /// Check whether to only select files that have expired.
+    #[cfg(feature = "history")]
+    pub fn expired(&self) -> bool {
+        self.matches.is_present("expired")
+    }
+
+    /// Check whether to only select files that still exist.
+    #[cfg(feature = "history")]
+    pub fn active(&self) -> bool {
+        self.matches.is_present("active")
+    }
+
+    /// Get the filter to select history files to delete with, if specified.
+    #[cfg(feature = "history")]
+    pub fn filter(&'a self) -> Option<&'a str> {
+        self.matches.value_of("filter")
+    }
 }
 
 impl<'a> Matcher<'a> for DeleteMatcher<'a> {
@@ -1,12 +1,24 @@
+#[cfg(feature = "qrcode")]
+use std::path::Path;
 use std::path::PathBuf;
 
 use clap::ArgMatches;
+#[cfg(feature = "qrcode")]
+use failure::Fail;
+#[cfg(feature = "qrcode")]
+use ffsend_api::file::remote_file::RemoteFile;
 use ffsend_api::url::Url;
 
 use super::Matcher;
 use crate::cmd::arg::{ArgPassword, ArgUrl, CmdArgOption};
+#[cfg(feature = "qrcode")]
+use crate::host::parse_host;
+#[cfg(feature = "qrcode")]
+use crate::qrcode;
 #[cfg(feature = "archive")]
 use crate::util::env_var_present;
+#[cfg(feature = "qrcode")]
+use crate::util::{quit_error, quit_error_msg, ErrorHints, ErrorHintsBuilder};
 
 /// The download command matcher.
 pub struct DownloadMatcher<'a> {
@@ -20,6 +32,13 @@ impl<'a: 'b, 'b> DownloadMatcher<'a> {
     /// If the given URL is invalid,
     /// the program will quit with an error message.
     pub fn url(&'a self) -> Url {
+        #[cfg(feature = "qrcode")]
+        {
+            if let Some(path) = self.matches.value_of("qr") {
+                return url_from_qr(Path::new(path));
+            }
+        }
+
         ArgUrl::value(self.matches)
     }
 
@@ -40,6 +59,20 @@ impl<'a: 'b, 'b> DownloadMatcher<'a> {
         ArgPassword::value(self.matches)
     }
 
+    /// Get the secret key, if specified separately from the share URL.
+    pub fn key(&'a self) -> Option<&'a str> {
+        self.matches
+            .value_of("key")
+            .map(|key| key.trim().trim_start_matches('#'))
+    }
+
+    /// Get the expected SHA-256 checksum of the downloaded file, in lowercase hexadecimal notation.
+    pub fn expect_sha256(&'a self) -> Option<String> {
+        self.matches
+            .value_of("expect-sha256")
+            .map(|checksum| checksum.trim().to_lowercase())
+    }
+
     /// The target file or directory to download the file to.
     /// If a directory is given, the file name of the original uploaded file
     /// will be used.
@@ -64,3 +97,29 @@ impl<'a> Matcher<'a> for DownloadMatcher<'a> {
             .map(|matches| DownloadMatcher { matches })
     }
 }
+
+/// Scan the share URL from the QR code in the image at the given `path`.
+///
+/// The QR code must contain a valid share URL.
+/// The program will quit with an error message if no share URL could be obtained.
+#[cfg(feature = "qrcode")]
+fn url_from_qr(path: &Path) -> Url {
+    let content = match qrcode::scan(path) {
+        Ok(content) => content,
+        Err(err) => quit_error(
+            err.context("failed to scan share URL from QR code image"),
+            ErrorHints::default(),
+        ),
+    };
+
+    match parse_host(content.trim())
+        .ok()
+        .filter(|url| RemoteFile::parse_url(url.clone(), None).is_ok())
+    {
+        Some(url) => url,
+        None => quit_error_msg(
+            "the QR code does not contain a valid share URL",
+            ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+        ),
+    }
+}
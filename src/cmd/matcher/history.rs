@@ -3,8 +3,12 @@ use failure::Fail;
 use ffsend_api::url::Url;
 
 use super::Matcher;
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::{ArgCopyMode, ArgFormatMessage, CmdArgOption};
 use crate::host::parse_host;
-use crate::util::{quit_error, ErrorHints};
+#[cfg(feature = "clipboard")]
+use crate::message::CopyMode;
+use crate::util::{quit_error, quit_error_msg, ErrorHints};
 
 /// The history command matcher.
 pub struct HistoryMatcher<'a> {
@@ -36,6 +40,54 @@ impl<'a> HistoryMatcher<'a> {
             ),
         }
     }
+
+    /// Get the history entry and alias to set for it, if specified.
+    ///
+    /// The entry is returned as given by the user, and must be resolved through the history.
+    /// The program will quit with an error message if the alias is empty, an URL or a plain
+    /// number, as it would be mistaken for an index.
+    pub fn alias(&'a self) -> Option<(&'a str, &'a str)> {
+        // Get the entry and alias
+        let mut values = self.matches.values_of("alias")?;
+        let entry = values.next()?;
+        let alias = values.next()?.trim();
+
+        // The alias must not be empty, or look like an URL or index
+        if alias.is_empty() || alias.contains("://") {
+            quit_error_msg(
+                "the alias must not be empty or an URL",
+                ErrorHints::default(),
+            );
+        }
+        if alias.trim_start_matches('#').parse::<usize>().is_ok() {
+            quit_error_msg(
+                "the alias must not be a number, it is used to reference entries by index",
+                ErrorHints::default(),
+            );
+        }
+
+        Some((entry, alias))
+    }
+
+    /// Get the history entry to copy to the user's clipboard, if specified.
+    ///
+    /// The entry is returned as given by the user, and must be resolved through the history.
+    #[cfg(feature = "clipboard")]
+    pub fn copy(&'a self) -> Option<&'a str> {
+        self.matches.value_of("copy")
+    }
+
+    /// Get what to copy of the history entry.
+    ///
+    /// The share message is copied by default if a message template is given.
+    #[cfg(feature = "clipboard")]
+    pub fn copy_mode(&'a self) -> CopyMode {
+        ArgCopyMode::value(self.matches).unwrap_or_else(|| {
+            ArgFormatMessage::value(self.matches)
+                .map(CopyMode::Message)
+                .unwrap_or(CopyMode::Url)
+        })
+    }
 }
 
 impl<'a> Matcher<'a> for HistoryMatcher<'a> {
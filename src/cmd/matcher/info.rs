@@ -3,7 +3,11 @@ use ffsend_api::url::Url;
 use clap::ArgMatches;
 
 use super::Matcher;
-use crate::cmd::arg::{ArgOwner, ArgPassword, ArgUrl, CmdArgOption};
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::ArgCopyMode;
+use crate::cmd::arg::{ArgFormatMessage, ArgOwner, ArgPassword, ArgUrl, CmdArgOption};
+#[cfg(feature = "clipboard")]
+use crate::message::CopyMode;
 
 /// The info command matcher.
 pub struct InfoMatcher<'a> {
@@ -31,6 +35,38 @@ impl<'a: 'b, 'b> InfoMatcher<'a> {
     pub fn password(&'a self) -> Option<String> {
         ArgPassword::value(self.matches)
     }
+
+    /// Get the name of the single field to print, if specified.
+    pub fn field(&'a self) -> Option<String> {
+        self.matches.value_of("field").map(str::to_lowercase)
+    }
+
+    /// Get the share message template, if specified.
+    pub fn format_message(&'a self) -> Option<String> {
+        ArgFormatMessage::value(self.matches)
+    }
+
+    /// Check whether to print a share message instead of the report.
+    ///
+    /// Only if a message template is given on the command line, and no field is selected.
+    pub fn print_message(&'a self) -> bool {
+        ArgFormatMessage::given(self.matches) && self.field().is_none()
+    }
+
+    /// Check whether to copy to the user's clipboard, get the copy mode.
+    ///
+    /// The share message is copied by default if a message template is given.
+    #[cfg(feature = "clipboard")]
+    pub fn copy(&'a self) -> Option<CopyMode> {
+        if !self.matches.is_present("copy") && !ArgCopyMode::given(self.matches) {
+            return None;
+        }
+        Some(ArgCopyMode::value(self.matches).unwrap_or_else(|| {
+            self.format_message()
+                .map(CopyMode::Message)
+                .unwrap_or(CopyMode::Url)
+        }))
+    }
 }
 
 impl<'a> Matcher<'a> for InfoMatcher<'a> {
@@ -1,4 +1,3 @@
-#[cfg(feature = "history")]
 use std::path::PathBuf;
 
 use clap::ArgMatches;
@@ -6,7 +5,11 @@ use ffsend_api::api::DesiredVersion;
 
 use super::Matcher;
 use crate::cmd::arg::{ArgApi, ArgBasicAuth, CmdArgOption};
-use crate::util::{env_var_present, parse_duration};
+#[cfg(feature = "secret-store")]
+use crate::secret_store::Backend;
+#[cfg(feature = "clipboard")]
+use crate::util::ClipboardType;
+use crate::util::{env_var_present, parse_duration, PasswordSource};
 #[cfg(feature = "history")]
 use crate::util::{quit_error_msg, ErrorHintsBuilder};
 
@@ -41,6 +44,34 @@ impl<'a: 'b, 'b> MainMatcher<'a> {
         ArgBasicAuth::value(self.matches)
     }
 
+    /// Get the source to read passwords from instead of prompting, if set.
+    ///
+    /// A password file takes precedence over a file descriptor, which takes precedence over a
+    /// password command.
+    pub fn password_source(&self) -> Option<PasswordSource> {
+        if let Some(path) = self.matches.value_of("password-file") {
+            return Some(PasswordSource::File(PathBuf::from(path)));
+        }
+        #[cfg(unix)]
+        {
+            if let Some(fd) = self.matches.value_of("password-fd") {
+                return Some(PasswordSource::Fd(fd.parse().expect("invalid password-fd")));
+            }
+        }
+        self.matches
+            .value_of("password-cmd")
+            .map(|cmd| PasswordSource::Cmd(cmd.into()))
+    }
+
+    /// Check whether a password source is explicitly given on the command line.
+    ///
+    /// A password command set through the environment is only used when a password is needed.
+    pub fn password_source_given(&self) -> bool {
+        ["password-file", "password-fd", "password-cmd"]
+            .iter()
+            .any(|name| self.matches.occurrences_of(name) > 0)
+    }
+
     /// Get the history file to use.
     #[cfg(feature = "history")]
     pub fn history(&self) -> PathBuf {
@@ -61,6 +92,15 @@ impl<'a: 'b, 'b> MainMatcher<'a> {
         }
     }
 
+    /// Get where to store owner tokens.
+    #[cfg(feature = "secret-store")]
+    pub fn secret_store(&self) -> Backend {
+        self.matches
+            .value_of("secret-store")
+            .and_then(Backend::from_name)
+            .unwrap_or(Backend::History)
+    }
+
     /// Get the timeout in seconds
     pub fn timeout(&self) -> u64 {
         self.matches
@@ -77,6 +117,40 @@ impl<'a: 'b, 'b> MainMatcher<'a> {
             .expect("invalid transfer-timeout value") as u64
     }
 
+    /// Get the number of times to retry requests failing with a transient network error.
+    pub fn retries(&self) -> u32 {
+        self.matches
+            .value_of("retries")
+            .and_then(|arg| arg.parse().ok())
+            .expect("invalid retries value")
+    }
+
+    /// Get the time in seconds to wait before the first request retry.
+    pub fn retry_backoff(&self) -> u64 {
+        self.matches
+            .value_of("retry-backoff")
+            .and_then(|arg| parse_duration(arg).ok())
+            .expect("invalid retry-backoff value") as u64
+    }
+
+    /// Get the clipboard backend to use.
+    #[cfg(feature = "clipboard")]
+    pub fn clipboard(&self) -> ClipboardType {
+        self.matches
+            .value_of("clipboard-backend")
+            .and_then(ClipboardType::from_name)
+            .unwrap_or_else(ClipboardType::select)
+    }
+
+    /// Get the delay in seconds after which to clear the clipboard, if set.
+    #[cfg(feature = "clipboard")]
+    pub fn clipboard_clear(&self) -> Option<u64> {
+        self.matches
+            .value_of("clipboard-clear")
+            .map(|arg| parse_duration(arg).expect("invalid clipboard-clear value") as u64)
+            .filter(|delay| *delay > 0)
+    }
+
     /// Check whether we are incognito from the file history.
     #[cfg(feature = "history")]
     pub fn incognito(&self) -> bool {
@@ -1,3 +1,5 @@
+#[cfg(feature = "clipboard")]
+pub mod clear_clipboard;
 pub mod debug;
 pub mod delete;
 pub mod download;
@@ -9,10 +11,21 @@ pub mod info;
 pub mod main;
 pub mod params;
 pub mod password;
+#[cfg(feature = "qrcode")]
+pub mod qr;
+#[cfg(feature = "history")]
+pub mod queue;
+#[cfg(feature = "history")]
+pub mod reap;
+#[cfg(feature = "history")]
+pub mod schedule_delete;
 pub mod upload;
 pub mod version;
+pub mod watch;
 
 // Re-export to matcher module
+#[cfg(feature = "clipboard")]
+pub use self::clear_clipboard::ClearClipboardMatcher;
 pub use self::debug::DebugMatcher;
 pub use self::delete::DeleteMatcher;
 pub use self::download::DownloadMatcher;
@@ -24,8 +37,17 @@ pub use self::info::InfoMatcher;
 pub use self::main::MainMatcher;
 pub use self::params::ParamsMatcher;
 pub use self::password::PasswordMatcher;
-pub use self::upload::{CopyMode, UploadMatcher};
+#[cfg(feature = "qrcode")]
+pub use self::qr::QrMatcher;
+#[cfg(feature = "history")]
+pub use self::queue::QueueMatcher;
+#[cfg(feature = "history")]
+pub use self::reap::ReapMatcher;
+#[cfg(feature = "history")]
+pub use self::schedule_delete::ScheduleDeleteMatcher;
+pub use self::upload::UploadMatcher;
 pub use self::version::VersionMatcher;
+pub use self::watch::WatchMatcher;
 
 use clap::ArgMatches;
 
@@ -1,5 +1,4 @@
 use clap::ArgMatches;
-use ffsend_api::api::Version as ApiVersion;
 use ffsend_api::url::Url;
 
 use super::Matcher;
@@ -7,6 +6,7 @@ use crate::cmd::{
     arg::{ArgDownloadLimit, ArgOwner, ArgUrl, CmdArgOption},
     matcher::MainMatcher,
 };
+use crate::limits::Limits;
 
 /// The params command matcher.
 pub struct ParamsMatcher<'a> {
@@ -36,10 +36,10 @@ impl<'a: 'b, 'b> ParamsMatcher<'a> {
     pub fn download_limit(
         &'a self,
         main_matcher: &MainMatcher,
-        api_version: ApiVersion,
+        limits: &Limits,
         auth: bool,
-    ) -> Option<usize> {
-        ArgDownloadLimit::value_checked(self.matches, main_matcher, api_version, auth)
+    ) -> Option<u8> {
+        ArgDownloadLimit::value_checked(self.matches, main_matcher, limits, auth)
     }
 }
 
@@ -2,9 +2,12 @@ use clap::ArgMatches;
 use ffsend_api::url::Url;
 use rpassword::prompt_password_stderr;
 
-use crate::cmd::arg::{ArgGenPassphrase, ArgOwner, ArgPassword, ArgUrl, CmdArgFlag, CmdArgOption};
+use crate::cmd::arg::{
+    ArgGenPassphrase, ArgOwner, ArgPassword, ArgPasswordPolicy, ArgUrl, CmdArgOption,
+};
 use crate::cmd::matcher::{MainMatcher, Matcher};
-use crate::util::check_empty_password;
+use crate::password_policy::Mode;
+use crate::util::{check_empty_password, read_password_source};
 
 /// The password command matcher.
 pub struct PasswordMatcher<'a> {
@@ -33,12 +36,17 @@ impl<'a: 'b, 'b> PasswordMatcher<'a> {
     /// `(password, generated)`
     pub fn password(&'a self) -> (String, bool) {
         // Generate a passphrase if requested
-        if ArgGenPassphrase::is_present(self.matches) {
-            return (ArgGenPassphrase::gen_passphrase(), true);
+        if ArgGenPassphrase::requested(self.matches) {
+            return (ArgGenPassphrase::gen_passphrase(self.matches), true);
         }
 
-        // Get the password, or prompt for it
-        let password = match ArgPassword::value(self.matches) {
+        // Create a main matcher
+        let matcher_main = MainMatcher::with(self.matches).unwrap();
+
+        // Get the password, read it from the password source, or prompt for it
+        let password = match ArgPassword::value(self.matches)
+            .or_else(|| read_password_source(&matcher_main))
+        {
             Some(password) => password,
             None => {
                 // Prompt for the password
@@ -49,14 +57,21 @@ impl<'a: 'b, 'b> PasswordMatcher<'a> {
             }
         };
 
-        // Create a main matcher
-        let matcher_main = MainMatcher::with(self.matches).unwrap();
-
         // Check for empty passwords
         check_empty_password(&password, &matcher_main);
 
         (password, false)
     }
+
+    /// Get the password policy mode to apply to chosen passwords, if set.
+    pub fn password_policy(&self) -> Option<Mode> {
+        ArgPasswordPolicy::value(self.matches)
+    }
+
+    /// Estimate the entropy of generated passphrases in bits, based on the selected policy.
+    pub fn passphrase_entropy(&self) -> Option<f64> {
+        ArgGenPassphrase::entropy(self.matches)
+    }
 }
 
 impl<'a> Matcher<'a> for PasswordMatcher<'a> {
@@ -0,0 +1,39 @@
+use clap::ArgMatches;
+use ffsend_api::url::Url;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgQrEcLevel, ArgQrFormat, ArgQrOutput, ArgUrl, CmdArgOption};
+use crate::qrcode::QrOptions;
+
+/// The QR code command matcher.
+pub struct QrMatcher<'a> {
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a: 'b, 'b> QrMatcher<'a> {
+    /// Get the file share URL.
+    ///
+    /// This method parses the URL into an `Url`.
+    /// If the given URL is invalid,
+    /// the program will quit with an error message.
+    pub fn url(&'a self) -> Url {
+        ArgUrl::value(self.matches)
+    }
+
+    /// Get the QR code rendering options.
+    pub fn options(&'a self) -> QrOptions {
+        QrOptions {
+            format: ArgQrFormat::value(self.matches),
+            ec_level: ArgQrEcLevel::value(self.matches),
+            output: ArgQrOutput::value(self.matches),
+        }
+    }
+}
+
+impl<'a> Matcher<'a> for QrMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("qr")
+            .map(|matches| QrMatcher { matches })
+    }
+}
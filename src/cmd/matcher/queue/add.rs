@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::util::{parse_datetime, quit_error_msg, ErrorHintsBuilder};
+
+/// The queue add command matcher.
+pub struct QueueAddMatcher<'a> {
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> QueueAddMatcher<'a> {
+    /// Get the files to upload.
+    pub fn files(&'a self) -> Vec<&'a str> {
+        self.matches
+            .values_of("FILE")
+            .expect("no files specified")
+            .collect()
+    }
+
+    /// Get the time to upload at, if specified.
+    ///
+    /// If the time isn't in the future, the program quits with an error message.
+    pub fn at(&self) -> Option<DateTime<Utc>> {
+        let at = parse_datetime(self.matches.value_of("at")?).expect("invalid time");
+        if at <= Utc::now() {
+            quit_error_msg(
+                "the upload time must be in the future",
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            );
+        }
+        Some(at)
+    }
+
+    /// Get the options to upload with.
+    pub fn upload_options(&'a self) -> Vec<String> {
+        self.matches
+            .values_of("UPLOAD_OPTIONS")
+            .map(|options| options.map(|option| option.to_owned()).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl<'a> Matcher<'a> for QueueAddMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("queue")?
+            .subcommand_matches("add")
+            .map(|matches| QueueAddMatcher { matches })
+    }
+}
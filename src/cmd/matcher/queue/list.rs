@@ -0,0 +1,18 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The queue list command matcher.
+pub struct QueueListMatcher<'a> {
+    #[allow(unused)]
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> Matcher<'a> for QueueListMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("queue")?
+            .subcommand_matches("list")
+            .map(|matches| QueueListMatcher { matches })
+    }
+}
@@ -0,0 +1,50 @@
+pub mod add;
+pub mod list;
+pub mod remove;
+pub mod run;
+
+use clap::ArgMatches;
+
+use super::Matcher;
+use add::QueueAddMatcher;
+use list::QueueListMatcher;
+use remove::QueueRemoveMatcher;
+use run::QueueRunMatcher;
+
+/// The queue command matcher.
+pub struct QueueMatcher<'a> {
+    root: &'a ArgMatches<'a>,
+    _matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> QueueMatcher<'a> {
+    /// Get the queue add sub command, if matched.
+    pub fn matcher_add(&'a self) -> Option<QueueAddMatcher> {
+        QueueAddMatcher::with(self.root)
+    }
+
+    /// Get the queue list sub command, if matched.
+    pub fn matcher_list(&'a self) -> Option<QueueListMatcher> {
+        QueueListMatcher::with(self.root)
+    }
+
+    /// Get the queue remove sub command, if matched.
+    pub fn matcher_remove(&'a self) -> Option<QueueRemoveMatcher> {
+        QueueRemoveMatcher::with(self.root)
+    }
+
+    /// Get the queue run sub command, if matched.
+    pub fn matcher_run(&'a self) -> Option<QueueRunMatcher> {
+        QueueRunMatcher::with(self.root)
+    }
+}
+
+impl<'a> Matcher<'a> for QueueMatcher<'a> {
+    fn with(root: &'a ArgMatches) -> Option<Self> {
+        root.subcommand_matches("queue")
+            .map(|matches| QueueMatcher {
+                root,
+                _matches: matches,
+            })
+    }
+}
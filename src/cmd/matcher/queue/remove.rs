@@ -0,0 +1,28 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The queue remove command matcher.
+pub struct QueueRemoveMatcher<'a> {
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> QueueRemoveMatcher<'a> {
+    /// Get the IDs of the jobs to remove.
+    pub fn ids(&self) -> Vec<u32> {
+        self.matches
+            .values_of("ID")
+            .expect("no job IDs specified")
+            .map(|id| id.trim_start_matches('#').parse().expect("invalid job ID"))
+            .collect()
+    }
+}
+
+impl<'a> Matcher<'a> for QueueRemoveMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("queue")?
+            .subcommand_matches("remove")
+            .map(|matches| QueueRemoveMatcher { matches })
+    }
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use clap::ArgMatches;
+
+use super::Matcher;
+use crate::util::parse_duration;
+
+/// The queue run command matcher.
+pub struct QueueRunMatcher<'a> {
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> QueueRunMatcher<'a> {
+    /// Check whether to upload all queued files, also those scheduled later.
+    pub fn all(&self) -> bool {
+        self.matches.is_present("all")
+    }
+
+    /// Check whether to wait for files scheduled later.
+    pub fn wait(&self) -> bool {
+        self.matches.is_present("wait")
+    }
+
+    /// Get the number of times to try each upload.
+    pub fn attempts(&self) -> u32 {
+        self.matches
+            .value_of("attempts")
+            .and_then(|arg| arg.parse().ok())
+            .expect("invalid attempts value")
+    }
+
+    /// Get the time to wait before retrying a failed upload.
+    pub fn retry_delay(&self) -> Duration {
+        self.matches
+            .value_of("retry-delay")
+            .and_then(|arg| parse_duration(arg).ok())
+            .map(|secs| Duration::from_secs(secs as u64))
+            .expect("invalid retry delay value")
+    }
+}
+
+impl<'a> Matcher<'a> for QueueRunMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("queue")?
+            .subcommand_matches("run")
+            .map(|matches| QueueRunMatcher { matches })
+    }
+}
@@ -0,0 +1,23 @@
+use clap::ArgMatches;
+
+use super::Matcher;
+
+/// The reap command matcher.
+pub struct ReapMatcher<'a> {
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> ReapMatcher<'a> {
+    /// Check whether to only list the files due for deletion.
+    pub fn dry_run(&self) -> bool {
+        self.matches.is_present("dry-run")
+    }
+}
+
+impl<'a> Matcher<'a> for ReapMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("reap")
+            .map(|matches| ReapMatcher { matches })
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use clap::ArgMatches;
+use ffsend_api::url::Url;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgOwner, ArgUrl, CmdArgOption};
+use crate::util::{parse_datetime, quit_error_msg, ErrorHintsBuilder};
+
+/// The schedule delete command matcher.
+pub struct ScheduleDeleteMatcher<'a> {
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> ScheduleDeleteMatcher<'a> {
+    /// Get the file share URL.
+    ///
+    /// This method parses the URL into an `Url`.
+    /// If the given URL is invalid,
+    /// the program will quit with an error message.
+    pub fn url(&'a self) -> Url {
+        ArgUrl::value(self.matches)
+    }
+
+    /// Get the owner token.
+    pub fn owner(&'a self) -> Option<String> {
+        ArgOwner::value(self.matches)
+    }
+
+    /// Get the time to delete the file at.
+    ///
+    /// `None` is returned if the scheduled deletion should be cancelled.
+    /// If no time is given, the program quits with an error message.
+    pub fn delete_at(&self) -> Option<DateTime<Utc>> {
+        if self.matches.is_present("cancel") {
+            return None;
+        }
+        match self.matches.value_of("TIME") {
+            Some(time) => Some(parse_datetime(time).expect("invalid time")),
+            None => quit_error_msg(
+                "no time given to delete the file at",
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            ),
+        }
+    }
+}
+
+impl<'a> Matcher<'a> for ScheduleDeleteMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("schedule-delete")
+            .map(|matches| ScheduleDeleteMatcher { matches })
+    }
+}
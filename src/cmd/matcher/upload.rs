@@ -1,15 +1,44 @@
+use std::str::FromStr;
+
+#[cfg(feature = "history")]
+use chrono::{DateTime, Utc};
 use clap::ArgMatches;
-use ffsend_api::{api::Version as ApiVersion, config, url::Url};
+use ffsend_api::url::Url;
 
 use super::Matcher;
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::ArgCopyMode;
+#[cfg(feature = "qrcode")]
+use crate::cmd::arg::{ArgQrEcLevel, ArgQrFormat, ArgQrOutput};
 use crate::cmd::{
     arg::{
-        ArgDownloadLimit, ArgExpiryTime, ArgGenPassphrase, ArgHost, ArgPassword, CmdArgFlag,
-        CmdArgOption,
+        ArgDownloadLimit, ArgExpiryTime, ArgFormatMessage, ArgGenPassphrase, ArgHost, ArgPassword,
+        ArgPasswordPolicy, CmdArgFlag, CmdArgOption,
     },
     matcher::MainMatcher,
 };
-use crate::util::{bin_name, env_var_present, quit_error_msg, ErrorHintsBuilder};
+use crate::limits::Limits;
+#[cfg(feature = "mail")]
+use crate::mail::{
+    parse_address, Mail, Transport as MailTransport, BODY_DEFAULT as MAIL_BODY_DEFAULT,
+    SUBJECT_DEFAULT as MAIL_SUBJECT_DEFAULT,
+};
+#[cfg(feature = "clipboard")]
+use crate::message::CopyMode;
+use crate::password_policy::Mode;
+#[cfg(feature = "qrcode")]
+use crate::qrcode::QrOptions;
+#[cfg(feature = "urlshorten")]
+use crate::urlshorten::{Shortener, SHORTENER_DEFAULT};
+#[cfg(feature = "urlshorten")]
+use crate::util::bin_name;
+#[cfg(feature = "mail")]
+use crate::util::highlight;
+#[cfg(feature = "history")]
+use crate::util::parse_datetime;
+#[cfg(feature = "urlshorten")]
+use crate::util::quit_error;
+use crate::util::{env_var_present, parse_bytes, quit_error_msg, ErrorHintsBuilder};
 
 /// The upload command matcher.
 pub struct UploadMatcher<'a> {
@@ -68,14 +97,29 @@ impl<'a: 'b, 'b> UploadMatcher<'a> {
     /// `(password, generated)`
     pub fn password(&'a self) -> Option<(String, bool)> {
         // Generate a passphrase if requested
-        if ArgGenPassphrase::is_present(self.matches) {
-            return Some((ArgGenPassphrase::gen_passphrase(), true));
+        if ArgGenPassphrase::requested(self.matches) {
+            return Some((self.gen_passphrase(), true));
         }
 
         // Use a specified password or use nothing
         ArgPassword::value(self.matches).map(|password| (password, false))
     }
 
+    /// Generate a passphrase based on the selected passphrase policy.
+    pub fn gen_passphrase(&self) -> String {
+        ArgGenPassphrase::gen_passphrase(self.matches)
+    }
+
+    /// Get the password policy mode to apply to chosen passwords, if set.
+    pub fn password_policy(&self) -> Option<Mode> {
+        ArgPasswordPolicy::value(self.matches)
+    }
+
+    /// Estimate the entropy of generated passphrases in bits, based on the selected policy.
+    pub fn passphrase_entropy(&self) -> Option<f64> {
+        ArgGenPassphrase::entropy(self.matches)
+    }
+
     /// Get the download limit.
     ///
     /// If the download limit was the default, `None` is returned to not
@@ -83,15 +127,15 @@ impl<'a: 'b, 'b> UploadMatcher<'a> {
     pub fn download_limit(
         &'a self,
         main_matcher: &MainMatcher,
-        api_version: ApiVersion,
+        limits: &Limits,
         auth: bool,
-    ) -> Option<usize> {
-        ArgDownloadLimit::value_checked(self.matches, main_matcher, api_version, auth).and_then(
-            |d| match d {
-                d if d == config::downloads_default(api_version, auth) => None,
+    ) -> Option<u8> {
+        ArgDownloadLimit::value_checked(self.matches, main_matcher, limits, auth).and_then(|d| {
+            match d {
+                d if usize::from(d) == limits.downloads_default => None,
                 d => Some(d),
-            },
-        )
+            }
+        })
     }
 
     /// Get the expiry time in seconds.
@@ -100,10 +144,15 @@ impl<'a: 'b, 'b> UploadMatcher<'a> {
     pub fn expiry_time(
         &'a self,
         main_matcher: &MainMatcher,
-        api_version: ApiVersion,
+        limits: &Limits,
         auth: bool,
     ) -> Option<usize> {
-        ArgExpiryTime::value_checked(self.matches, main_matcher, api_version, auth)
+        ArgExpiryTime::value_checked(self.matches, main_matcher, limits, auth)
+    }
+
+    /// Get the template to render a share message with, if set.
+    pub fn format_message(&self) -> Option<String> {
+        ArgFormatMessage::value(self.matches)
     }
 
     /// Check whether to archive the file to upload.
@@ -117,38 +166,183 @@ impl<'a: 'b, 'b> UploadMatcher<'a> {
         self.matches.is_present("open") || env_var_present("FFSEND_OPEN")
     }
 
+    /// Get the time to delete the file at, if scheduled.
+    ///
+    /// If the time isn't in the future, the program quits with an error message.
+    #[cfg(feature = "history")]
+    pub fn delete_at(&self) -> Option<DateTime<Utc>> {
+        let delete_at = parse_datetime(self.matches.value_of("delete-at")?).expect("invalid time");
+        if delete_at <= Utc::now() {
+            quit_error_msg(
+                "the deletion time must be in the future",
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            );
+        }
+        Some(delete_at)
+    }
+
+    /// Check whether to compute the checksum of the uploaded file.
+    pub fn checksum(&self) -> bool {
+        self.matches.is_present("checksum") || env_var_present("FFSEND_CHECKSUM")
+    }
+
+    /// Get the size to split large files into parts by, if set.
+    pub fn split(&self) -> Option<u64> {
+        self.matches
+            .value_of("split")
+            .map(|size| parse_bytes(size).expect("invalid split size"))
+    }
+
+    /// Get the recipients to create a separate share for, if any.
+    pub fn recipients(&self) -> Vec<Recipient> {
+        self.matches
+            .values_of("recipient")
+            .map(|values| {
+                values
+                    .map(|value| value.parse().expect("invalid recipient"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Check whether to to delete local files after uploading.
     pub fn delete(&self) -> bool {
         self.matches.is_present("delete")
     }
 
-    /// Check whether to copy the file URL in the user's clipboard, get the copy mode.
+    /// Check whether to copy to the user's clipboard, get the copy mode.
+    ///
+    /// The share message is copied by default if a message template is given.
     #[cfg(feature = "clipboard")]
     pub fn copy(&self) -> Option<CopyMode> {
         // Get the options
-        let copy = self.matches.is_present("copy") || env_var_present("FFSEND_COPY");
+        let copy = self.matches.is_present("copy")
+            || env_var_present("FFSEND_COPY")
+            || ArgCopyMode::given(self.matches);
         let copy_cmd = self.matches.is_present("copy-cmd") || env_var_present("FFSEND_COPY_CMD");
 
         // Return the corresponding copy mode
         if copy_cmd {
             Some(CopyMode::DownloadCmd)
         } else if copy {
-            Some(CopyMode::Url)
+            Some(ArgCopyMode::value(self.matches).unwrap_or_else(|| {
+                self.format_message()
+                    .map(CopyMode::Message)
+                    .unwrap_or(CopyMode::Url)
+            }))
         } else {
             None
         }
     }
 
+    /// Get what and how to mail the share link, if requested.
+    ///
+    /// If no sender address is configured, the program quits with an error message.
+    #[cfg(feature = "mail")]
+    pub fn mail(&self) -> Option<Mail> {
+        let to = self
+            .matches
+            .values_of("mail-to")?
+            .map(|to| parse_address(to).expect("invalid mail address"))
+            .collect();
+        let from = match self.matches.value_of("mail-from") {
+            Some(from) => parse_address(from).expect("invalid mail address"),
+            None => quit_error_msg(
+                "no address to mail the share link from",
+                ErrorHintsBuilder::default()
+                    .add_info(format!(
+                        "Use '{}' to specify the sender address",
+                        highlight("--mail-from <ADDR>")
+                    ))
+                    .force(false)
+                    .verbose(false)
+                    .build()
+                    .unwrap(),
+            ),
+        };
+        let transport = match self.matches.value_of("mail-smtp") {
+            Some(url) => MailTransport::Smtp(url.into()),
+            None => MailTransport::Sendmail(self.matches.value_of("mail-sendmail").map(Into::into)),
+        };
+
+        Some(Mail {
+            from,
+            to,
+            subject: self
+                .matches
+                .value_of("mail-subject")
+                .unwrap_or(MAIL_SUBJECT_DEFAULT)
+                .into(),
+            body: self
+                .matches
+                .value_of("mail-body")
+                .unwrap_or(MAIL_BODY_DEFAULT)
+                .into(),
+            password: self.matches.is_present("mail-password"),
+            transport,
+        })
+    }
+
     /// Check whether to shorten a share URL
     #[cfg(feature = "urlshorten")]
     pub fn shorten(&self) -> bool {
-        self.matches.is_present("shorten")
+        self.matches.is_present("shorten") || self.shorten_split()
+    }
+
+    /// Check whether to shorten the share URL without its secret key.
+    #[cfg(feature = "urlshorten")]
+    pub fn shorten_split(&self) -> bool {
+        self.matches.is_present("shorten-split") || env_var_present("FFSEND_SHORTEN_SPLIT")
+    }
+
+    /// Get the URL shortener to use.
+    ///
+    /// If the selected shortener is unknown or misconfigured,
+    /// the program will quit with an error message.
+    #[cfg(feature = "urlshorten")]
+    pub fn shortener(&self) -> Shortener {
+        let name = self
+            .matches
+            .value_of("shorten-provider")
+            .unwrap_or(SHORTENER_DEFAULT);
+        let key = self.matches.value_of("shorten-key").map(|key| key.into());
+        let url = self.matches.value_of("shorten-url").map(|url| url.into());
+
+        match Shortener::from_name(name, key, url) {
+            Ok(shortener) => shortener,
+            Err(err) => quit_error(
+                err,
+                ErrorHintsBuilder::default()
+                    .add_info(format!(
+                        "Use '{} upload --help' to list supported URL shorteners",
+                        bin_name(),
+                    ))
+                    .force(false)
+                    .verbose(false)
+                    .build()
+                    .unwrap(),
+            ),
+        }
     }
 
     /// Check whether to print a QR code for the share URL.
+    ///
+    /// This is implied when a QR code format or output file is given.
     #[cfg(feature = "qrcode")]
     pub fn qrcode(&self) -> bool {
         self.matches.is_present("qrcode")
+            || ArgQrFormat::is_present(self.matches)
+            || ArgQrOutput::is_present(self.matches)
+    }
+
+    /// Get the QR code rendering options.
+    #[cfg(feature = "qrcode")]
+    pub fn qrcode_options(&'a self) -> QrOptions {
+        QrOptions {
+            format: ArgQrFormat::value(self.matches),
+            ec_level: ArgQrEcLevel::value(self.matches),
+            output: ArgQrOutput::value(self.matches),
+        }
     }
 }
 
@@ -160,22 +354,44 @@ impl<'a> Matcher<'a> for UploadMatcher<'a> {
     }
 }
 
-/// The copy mode.
-#[derive(Debug, Copy, Clone)]
-pub enum CopyMode {
-    /// Copy the public share link.
-    Url,
+/// A recipient to create a separate share for.
+#[derive(Debug, Clone)]
+pub struct Recipient {
+    /// The name to label the share with.
+    pub name: String,
 
-    /// Copy an ffsend download command.
-    DownloadCmd,
+    /// The download limit for this recipient, if specified.
+    pub downloads: Option<usize>,
 }
 
-impl CopyMode {
-    /// Build the string to copy, based on the given `url` and current mode.
-    pub fn build(&self, url: &str) -> String {
-        match self {
-            CopyMode::Url => url.into(),
-            CopyMode::DownloadCmd => format!("{} download {}", bin_name(), url),
+impl FromStr for Recipient {
+    type Err = String;
+
+    /// Parse a recipient in the `NAME[:DOWNLOADS]` format.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, downloads) = match value.rsplit_once(':') {
+            Some((name, downloads)) => {
+                let downloads = downloads
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|d| *d > 0)
+                    .ok_or_else(|| {
+                        String::from("Recipient download limit must be a positive number")
+                    })?;
+                (name, Some(downloads))
+            }
+            None => (value, None),
+        };
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Recipient name must not be empty".into());
         }
+
+        Ok(Self {
+            name: name.into(),
+            downloads,
+        })
     }
 }
@@ -0,0 +1,62 @@
+use clap::ArgMatches;
+use ffsend_api::url::Url;
+
+use super::Matcher;
+use crate::cmd::arg::{ArgOwner, ArgUrl, CmdArgOption};
+use crate::util::parse_duration;
+
+/// The watch downloads command matcher.
+pub struct WatchMatcher<'a> {
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a: 'b, 'b> WatchMatcher<'a> {
+    /// Get the file share URL.
+    ///
+    /// This method parses the URL into an `Url`.
+    /// If the given URL is invalid,
+    /// the program will quit with an error message.
+    pub fn url(&'a self) -> Url {
+        ArgUrl::value(self.matches)
+    }
+
+    /// Get the owner token.
+    pub fn owner(&'a self) -> Option<String> {
+        ArgOwner::value(self.matches)
+    }
+
+    /// Check whether to watch all files from history.
+    #[cfg(feature = "history")]
+    pub fn all(&self) -> bool {
+        self.matches.is_present("all")
+    }
+
+    /// Get the polling interval in seconds.
+    pub fn interval(&self) -> u64 {
+        self.matches
+            .value_of("interval")
+            .and_then(|arg| parse_duration(arg).ok())
+            .expect("invalid interval value") as u64
+    }
+
+    /// Check whether to print events as JSON.
+    pub fn json(&self) -> bool {
+        self.matches
+            .value_of("format")
+            .map(|format| format.eq_ignore_ascii_case("json"))
+            .unwrap_or(false)
+    }
+
+    /// Get the shell command to run for each event, if specified.
+    pub fn exec(&'a self) -> Option<&'a str> {
+        self.matches.value_of("exec")
+    }
+}
+
+impl<'a> Matcher<'a> for WatchMatcher<'a> {
+    fn with(matches: &'a ArgMatches) -> Option<Self> {
+        matches
+            .subcommand_matches("watch-downloads")
+            .map(|matches| WatchMatcher { matches })
+    }
+}
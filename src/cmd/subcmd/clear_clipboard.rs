@@ -0,0 +1,35 @@
+use clap::{App, AppSettings, Arg, SubCommand};
+
+use crate::util::parse_duration;
+
+/// The clear clipboard command definition.
+///
+/// This command is hidden, and is invoked in the background to clear the clipboard after a delay.
+pub struct CmdClearClipboard;
+
+impl CmdClearClipboard {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        SubCommand::with_name("clear-clipboard")
+            .about("Clear the clipboard")
+            .setting(AppSettings::Hidden)
+            .arg(
+                Arg::with_name("delay")
+                    .long("delay")
+                    .value_name("SECONDS")
+                    .help("Time to wait before clearing the clipboard")
+                    .validator(|arg| {
+                        parse_duration(&arg).map(drop).map_err(|_| {
+                            String::from("Delay must be a positive number of seconds.")
+                        })
+                    }),
+            )
+            .arg(
+                Arg::with_name("content-hash-stdin")
+                    .long("content-hash-stdin")
+                    .help(
+                        "Read a SHA-256 hash from stdin, only clear the clipboard if its \
+                         content has this hash",
+                    ),
+            )
+    }
+}
@@ -1,5 +1,9 @@
 use clap::{App, SubCommand};
+#[cfg(feature = "history")]
+use clap::{Arg, ArgGroup};
 
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::ArgFromClipboard;
 use crate::cmd::arg::{ArgOwner, ArgUrl, CmdArg};
 
 /// The delete command definition.
@@ -7,11 +11,61 @@ pub struct CmdDelete;
 
 impl CmdDelete {
     pub fn build<'a, 'b>() -> App<'a, 'b> {
-        SubCommand::with_name("delete")
+        // Build the subcommand
+        let cmd = SubCommand::with_name("delete")
             .about("Delete a shared file")
             .visible_alias("del")
-            .visible_alias("rm")
-            .arg(ArgUrl::build())
-            .arg(ArgOwner::build())
+            .visible_alias("rm");
+
+        // With history support, allow deleting all files from history
+        #[cfg(feature = "history")]
+        let cmd = cmd
+            .arg(ArgUrl::build_required_unless(&["all", "recipient"]))
+            .arg(ArgOwner::build().conflicts_with_all(&["all", "recipient"]))
+            .arg(
+                Arg::with_name("all")
+                    .long("all")
+                    .short("a")
+                    .conflicts_with_all(&["URL", "from-clipboard"])
+                    .help("Delete all files in history with a known owner token"),
+            )
+            .arg(
+                Arg::with_name("recipient")
+                    .long("recipient")
+                    .short("R")
+                    .value_name("NAME")
+                    .conflicts_with_all(&["URL", "from-clipboard"])
+                    .help("Delete the shares in history created for recipient NAME"),
+            )
+            .arg(
+                Arg::with_name("filter")
+                    .long("filter")
+                    .short("F")
+                    .value_name("TEXT")
+                    .requires("history-select")
+                    .help("Only delete files with TEXT in their alias, name or share URL"),
+            )
+            .arg(
+                Arg::with_name("expired")
+                    .long("expired")
+                    .conflicts_with("active")
+                    .requires("history-select")
+                    .help("Only select files that expired, to remove them from history"),
+            )
+            .arg(
+                Arg::with_name("active")
+                    .long("active")
+                    .requires("history-select")
+                    .help("Only delete files that still exist on the remote host"),
+            )
+            .group(ArgGroup::with_name("history-select").args(&["all", "recipient"]));
+        #[cfg(not(feature = "history"))]
+        let cmd = cmd.arg(ArgUrl::build()).arg(ArgOwner::build());
+
+        // Optional clipboard support, to read the share URL from
+        #[cfg(feature = "clipboard")]
+        let cmd = cmd.arg(ArgFromClipboard::build());
+
+        cmd
     }
 }
@@ -1,6 +1,9 @@
 use clap::{App, Arg, SubCommand};
 
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::ArgFromClipboard;
 use crate::cmd::arg::{ArgPassword, ArgUrl, CmdArg};
+use crate::util::is_sha256_hex;
 
 /// The download command definition.
 pub struct CmdDownload;
@@ -13,8 +16,15 @@ impl CmdDownload {
             .about("Download files")
             .visible_alias("d")
             .visible_alias("down")
-            .arg(ArgUrl::build())
             .arg(ArgPassword::build())
+            .arg(
+                Arg::with_name("key")
+                    .long("key")
+                    .short("k")
+                    .alias("secret")
+                    .value_name("KEY")
+                    .help("The secret key, if not part of the share URL"),
+            )
             .arg(
                 Arg::with_name("output")
                     .long("output")
@@ -24,6 +34,22 @@ impl CmdDownload {
                     .alias("file")
                     .value_name("PATH")
                     .help("Output file or directory"),
+            )
+            .arg(
+                Arg::with_name("expect-sha256")
+                    .long("expect-sha256")
+                    .alias("sha256")
+                    .value_name("HEX")
+                    .validator(|arg| {
+                        if is_sha256_hex(arg.trim()) {
+                            Ok(())
+                        } else {
+                            Err(String::from(
+                                "Checksum must be a SHA-256 hash of 64 hexadecimal characters",
+                            ))
+                        }
+                    })
+                    .help("Verify the SHA-256 checksum of the downloaded file"),
             );
 
         // Optional archive support
@@ -40,6 +66,29 @@ impl CmdDownload {
             )
         }
 
+        // Optional clipboard support, to read the share URL from
+        #[cfg(feature = "clipboard")]
+        {
+            cmd = cmd.arg(ArgFromClipboard::build())
+        }
+
+        // Optional QR code support, to scan the share URL from an image
+        #[cfg(feature = "qrcode")]
+        {
+            cmd = cmd.arg(ArgUrl::build_required_unless(&["qr"])).arg(
+                Arg::with_name("qr")
+                    .long("qr")
+                    .alias("qrcode")
+                    .value_name("IMAGE")
+                    .conflicts_with_all(&["URL", "from-clipboard"])
+                    .help("Scan the share URL from a QR code image"),
+            )
+        }
+        #[cfg(not(feature = "qrcode"))]
+        {
+            cmd = cmd.arg(ArgUrl::build())
+        }
+
         cmd
     }
 }
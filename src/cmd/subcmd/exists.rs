@@ -1,5 +1,7 @@
 use clap::{App, SubCommand};
 
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::ArgFromClipboard;
 use crate::cmd::arg::{ArgUrl, CmdArg};
 
 /// The exists command definition.
@@ -7,10 +9,16 @@ pub struct CmdExists;
 
 impl CmdExists {
     pub fn build<'a, 'b>() -> App<'a, 'b> {
-        SubCommand::with_name("exists")
+        let cmd = SubCommand::with_name("exists")
             .about("Check whether a remote file exists")
             .visible_alias("e")
             .alias("exist")
-            .arg(ArgUrl::build())
+            .arg(ArgUrl::build());
+
+        // Optional clipboard support, to read the share URL from
+        #[cfg(feature = "clipboard")]
+        let cmd = cmd.arg(ArgFromClipboard::build());
+
+        cmd
     }
 }
@@ -1,11 +1,14 @@
 use clap::{App, Arg, SubCommand};
 
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::{ArgCopyMode, ArgFormatMessage, CmdArg};
+
 /// The history command definition.
 pub struct CmdHistory;
 
 impl CmdHistory {
     pub fn build<'a, 'b>() -> App<'a, 'b> {
-        SubCommand::with_name("history")
+        let cmd = SubCommand::with_name("history")
             .about("View file history")
             .visible_alias("h")
             .alias("ls")
@@ -17,12 +20,35 @@ impl CmdHistory {
                     .value_name("URL")
                     .help("Remove history entry"),
             )
+            .arg(
+                Arg::with_name("alias")
+                    .long("alias")
+                    .short("a")
+                    .value_names(&["ENTRY", "ALIAS"])
+                    .number_of_values(2)
+                    .help("Set an alias to reference a history entry by"),
+            )
             .arg(
                 Arg::with_name("clear")
                     .long("clear")
                     .short("C")
                     .alias("flush")
                     .help("Clear all history"),
+            );
+
+        // Optional clipboard support, to copy history entries with
+        #[cfg(feature = "clipboard")]
+        let cmd = cmd
+            .arg(
+                Arg::with_name("copy")
+                    .long("copy")
+                    .short("c")
+                    .value_name("ENTRY")
+                    .help("Copy a history entry to your clipboard"),
             )
+            .arg(ArgCopyMode::build())
+            .arg(ArgFormatMessage::build().help("Copy a share message rendered from a template"));
+
+        cmd
     }
 }
@@ -1,18 +1,45 @@
-use clap::{App, SubCommand};
+use clap::{App, Arg, SubCommand};
 
-use crate::cmd::arg::{ArgOwner, ArgPassword, ArgUrl, CmdArg};
+use crate::action::info::FIELDS;
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::{ArgCopyMode, ArgFromClipboard};
+use crate::cmd::arg::{ArgFormatMessage, ArgOwner, ArgPassword, ArgUrl, CmdArg};
 
 /// The info command definition.
 pub struct CmdInfo;
 
 impl CmdInfo {
     pub fn build<'a, 'b>() -> App<'a, 'b> {
-        SubCommand::with_name("info")
+        let cmd = SubCommand::with_name("info")
             .about("Fetch info about a shared file")
             .visible_alias("i")
             .alias("information")
             .arg(ArgUrl::build())
             .arg(ArgOwner::build())
             .arg(ArgPassword::build())
+            .arg(
+                Arg::with_name("field")
+                    .long("field")
+                    .short("F")
+                    .value_name("NAME")
+                    .possible_values(FIELDS)
+                    .case_insensitive(true)
+                    .help("Only print the value of the given field"),
+            )
+            .arg(ArgFormatMessage::build());
+
+        // Optional clipboard support, to read the share URL from and copy to
+        #[cfg(feature = "clipboard")]
+        let cmd = cmd
+            .arg(ArgFromClipboard::build())
+            .arg(
+                Arg::with_name("copy")
+                    .long("copy")
+                    .short("c")
+                    .help("Copy the share link to your clipboard"),
+            )
+            .arg(ArgCopyMode::build());
+
+        cmd
     }
 }
@@ -1,3 +1,5 @@
+#[cfg(feature = "clipboard")]
+pub mod clear_clipboard;
 pub mod debug;
 pub mod delete;
 pub mod download;
@@ -8,10 +10,21 @@ pub mod history;
 pub mod info;
 pub mod params;
 pub mod password;
+#[cfg(feature = "qrcode")]
+pub mod qr;
+#[cfg(feature = "history")]
+pub mod queue;
+#[cfg(feature = "history")]
+pub mod reap;
+#[cfg(feature = "history")]
+pub mod schedule_delete;
 pub mod upload;
 pub mod version;
+pub mod watch;
 
 // Re-export to cmd module
+#[cfg(feature = "clipboard")]
+pub use self::clear_clipboard::CmdClearClipboard;
 pub use self::debug::CmdDebug;
 pub use self::delete::CmdDelete;
 pub use self::download::CmdDownload;
@@ -22,5 +35,14 @@ pub use self::history::CmdHistory;
 pub use self::info::CmdInfo;
 pub use self::params::CmdParams;
 pub use self::password::CmdPassword;
+#[cfg(feature = "qrcode")]
+pub use self::qr::CmdQr;
+#[cfg(feature = "history")]
+pub use self::queue::CmdQueue;
+#[cfg(feature = "history")]
+pub use self::reap::CmdReap;
+#[cfg(feature = "history")]
+pub use self::schedule_delete::CmdScheduleDelete;
 pub use self::upload::CmdUpload;
 pub use self::version::CmdVersion;
+pub use self::watch::CmdWatch;
@@ -6,9 +6,15 @@ use crate::cmd::arg::{ArgDownloadLimit, ArgOwner, ArgUrl, CmdArg};
 pub struct CmdParams;
 
 impl CmdParams {
-    pub fn build<'a, 'b>() -> App<'a, 'b> {
+    /// Build the command, offering default values when building for shell `completions`.
+    pub fn build<'a, 'b>(completions: bool) -> App<'a, 'b> {
         // Create a list of parameter arguments, of which one is required
         let param_args = [ArgDownloadLimit::name()];
+        let download_limit = if completions {
+            ArgDownloadLimit::build_completions()
+        } else {
+            ArgDownloadLimit::build()
+        };
 
         SubCommand::with_name("parameters")
             .about("Change parameters of a shared file")
@@ -17,6 +23,6 @@ impl CmdParams {
             .alias("parameter")
             .arg(ArgUrl::build())
             .arg(ArgOwner::build())
-            .arg(ArgDownloadLimit::build().required_unless_one(&param_args))
+            .arg(download_limit.required_unless_one(&param_args))
     }
 }
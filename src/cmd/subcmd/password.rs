@@ -1,6 +1,6 @@
 use clap::{App, SubCommand};
 
-use crate::cmd::arg::{ArgGenPassphrase, ArgOwner, ArgPassword, ArgUrl, CmdArg};
+use crate::cmd::arg::{ArgGenPassphrase, ArgOwner, ArgPassword, ArgPasswordPolicy, ArgUrl, CmdArg};
 
 /// The password command definition.
 pub struct CmdPassword;
@@ -14,6 +14,8 @@ impl CmdPassword {
             .arg(ArgUrl::build())
             .arg(ArgPassword::build().help("Specify a password, do not prompt"))
             .arg(ArgGenPassphrase::build())
+            .args(&ArgGenPassphrase::build_policy())
+            .arg(ArgPasswordPolicy::build())
             .arg(ArgOwner::build())
     }
 }
@@ -0,0 +1,18 @@
+use clap::{App, SubCommand};
+
+use crate::cmd::arg::{ArgQrEcLevel, ArgQrFormat, ArgQrOutput, ArgUrl, CmdArg};
+
+/// The QR code command definition.
+pub struct CmdQr;
+
+impl CmdQr {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        SubCommand::with_name("qr")
+            .about("Render a QR code for a share URL")
+            .visible_alias("qrcode")
+            .arg(ArgUrl::build())
+            .arg(ArgQrFormat::build())
+            .arg(ArgQrOutput::build())
+            .arg(ArgQrEcLevel::build())
+    }
+}
@@ -0,0 +1,38 @@
+use clap::{App, Arg, SubCommand};
+
+use crate::util::parse_datetime;
+
+/// The queue add command definition.
+pub struct CmdQueueAdd;
+
+impl CmdQueueAdd {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        SubCommand::with_name("add")
+            .about("Queue an upload")
+            .arg(
+                Arg::with_name("FILE")
+                    .help("The file(s) to upload")
+                    .required(true)
+                    .multiple(true),
+            )
+            .arg(
+                Arg::with_name("at")
+                    .long("at")
+                    .value_name("TIME")
+                    .validator(|arg| parse_datetime(&arg).map(drop).map_err(|e| e.to_string()))
+                    .help("Upload at the given time, such as '2026-10-20 02:00' or '6h'"),
+            )
+            .arg(
+                Arg::with_name("UPLOAD_OPTIONS")
+                    .last(true)
+                    .multiple(true)
+                    .allow_hyphen_values(true)
+                    .help("Options to upload with, given after --")
+                    .long_help(
+                        "Options to upload with, given after --, such as \
+                         '-- --downloads 5 --password-file secret.txt'. Jobs are uploaded in \
+                         no-interact mode, so passwords must not be prompted for.",
+                    ),
+            )
+    }
+}
@@ -0,0 +1,12 @@
+use clap::{App, SubCommand};
+
+/// The queue list command definition.
+pub struct CmdQueueList;
+
+impl CmdQueueList {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        SubCommand::with_name("list")
+            .about("List queued uploads")
+            .visible_alias("ls")
+    }
+}
@@ -0,0 +1,27 @@
+pub mod add;
+pub mod list;
+pub mod remove;
+pub mod run;
+
+use clap::{App, AppSettings, SubCommand};
+
+use add::CmdQueueAdd;
+use list::CmdQueueList;
+use remove::CmdQueueRemove;
+use run::CmdQueueRun;
+
+/// The queue command definition.
+pub struct CmdQueue;
+
+impl CmdQueue {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        SubCommand::with_name("queue")
+            .about("Queue uploads to run later")
+            .visible_alias("q")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(CmdQueueAdd::build())
+            .subcommand(CmdQueueList::build())
+            .subcommand(CmdQueueRemove::build())
+            .subcommand(CmdQueueRun::build())
+    }
+}
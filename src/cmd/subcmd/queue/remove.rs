@@ -0,0 +1,24 @@
+use clap::{App, Arg, SubCommand};
+
+/// The queue remove command definition.
+pub struct CmdQueueRemove;
+
+impl CmdQueueRemove {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        SubCommand::with_name("remove")
+            .about("Remove queued uploads")
+            .visible_alias("rm")
+            .arg(
+                Arg::with_name("ID")
+                    .help("The ID(s) of the jobs to remove, as listed")
+                    .required(true)
+                    .multiple(true)
+                    .validator(|arg| {
+                        arg.trim_start_matches('#')
+                            .parse::<u32>()
+                            .map(drop)
+                            .map_err(|_| String::from("Job ID must be a number"))
+                    }),
+            )
+    }
+}
@@ -0,0 +1,54 @@
+use clap::{App, Arg, SubCommand};
+
+use crate::util::parse_duration;
+
+/// The queue run command definition.
+pub struct CmdQueueRun;
+
+impl CmdQueueRun {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        SubCommand::with_name("run")
+            .about("Upload queued files that are due")
+            .long_about(
+                "Upload queued files that are due. Doesn't prompt, and exits with an error if \
+                 any upload failed, to run from cron. Failed uploads stay queued to retry in the \
+                 next run. Results are appended to the queue.log file in the data directory.",
+            )
+            .arg(
+                Arg::with_name("all")
+                    .long("all")
+                    .short("a")
+                    .help("Upload all queued files, also those scheduled later"),
+            )
+            .arg(
+                Arg::with_name("wait")
+                    .long("wait")
+                    .short("w")
+                    .conflicts_with("all")
+                    .help("Wait for files scheduled later, until the queue is empty"),
+            )
+            .arg(
+                Arg::with_name("attempts")
+                    .long("attempts")
+                    .value_name("COUNT")
+                    .default_value("3")
+                    .validator(|arg| match arg.parse::<u32>() {
+                        Ok(attempts) if attempts > 0 => Ok(()),
+                        _ => Err(String::from("Attempts must be a positive number")),
+                    })
+                    .help("Number of times to try each upload"),
+            )
+            .arg(
+                Arg::with_name("retry-delay")
+                    .long("retry-delay")
+                    .value_name("TIME")
+                    .default_value("1m")
+                    .validator(|arg| {
+                        parse_duration(&arg)
+                            .map(drop)
+                            .map_err(|_| String::from("Retry delay must be a valid duration"))
+                    })
+                    .help("Time to wait before retrying a failed upload"),
+            )
+    }
+}
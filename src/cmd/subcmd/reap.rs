@@ -0,0 +1,22 @@
+use clap::{App, Arg, SubCommand};
+
+/// The reap command definition.
+pub struct CmdReap;
+
+impl CmdReap {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        SubCommand::with_name("reap")
+            .about("Delete files in history past their scheduled deletion time")
+            .long_about(
+                "Delete files in history past their scheduled deletion time, as set with \
+                 'upload --delete-at' or the schedule-delete command. Doesn't prompt, and exits \
+                 with an error if any file failed to delete, to run from cron.",
+            )
+            .arg(
+                Arg::with_name("dry-run")
+                    .long("dry-run")
+                    .short("n")
+                    .help("Only list the files that are due for deletion"),
+            )
+    }
+}
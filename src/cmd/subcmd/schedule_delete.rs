@@ -0,0 +1,44 @@
+use clap::{App, Arg, SubCommand};
+
+#[cfg(feature = "clipboard")]
+use crate::cmd::arg::ArgFromClipboard;
+use crate::cmd::arg::{ArgOwner, ArgUrl, CmdArg};
+use crate::util::parse_datetime;
+
+/// The schedule delete command definition.
+pub struct CmdScheduleDelete;
+
+impl CmdScheduleDelete {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        let cmd = SubCommand::with_name("schedule-delete")
+            .about("Schedule deleting a shared file at a given time")
+            .visible_alias("schedule")
+            .arg(ArgUrl::build())
+            .arg(ArgOwner::build())
+            .arg(
+                Arg::with_name("TIME")
+                    .index(2)
+                    .validator(|arg| parse_datetime(&arg).map(drop).map_err(|e| e.to_string()))
+                    .help("The time to delete the file at, such as '2026-10-20 17:00' or '3d'")
+                    .long_help(
+                        "The time to delete the file at. A local date and time such as \
+                         '2026-10-20 17:00', a date, or a duration from now such as '3d12h'. \
+                         Files are deleted by running the reap command once this time has \
+                         passed, such as from cron.",
+                    ),
+            )
+            .arg(
+                Arg::with_name("cancel")
+                    .long("cancel")
+                    .short("c")
+                    .conflicts_with("TIME")
+                    .help("Cancel the scheduled deletion"),
+            );
+
+        // Optional clipboard support, to read the share URL from
+        #[cfg(feature = "clipboard")]
+        let cmd = cmd.arg(ArgFromClipboard::build());
+
+        cmd
+    }
+}
@@ -0,0 +1,77 @@
+use clap::{App, Arg, SubCommand};
+
+use crate::cmd::arg::{ArgOwner, ArgUrl, CmdArg};
+use crate::util::parse_duration;
+
+/// The watch downloads command definition.
+pub struct CmdWatch;
+
+impl CmdWatch {
+    pub fn build<'a, 'b>() -> App<'a, 'b> {
+        // Build the subcommand
+        let cmd = SubCommand::with_name("watch-downloads")
+            .about("Watch shared files for downloads and expiry")
+            .visible_alias("watch")
+            .arg(ArgOwner::build())
+            .arg(
+                Arg::with_name("interval")
+                    .long("interval")
+                    .value_name("TIME")
+                    .default_value("1m")
+                    .validator(|arg| {
+                        parse_duration(&arg)
+                            .map_err(|_| String::from("Interval must be a valid duration"))
+                            .and_then(|secs| {
+                                if secs > 0 {
+                                    Ok(())
+                                } else {
+                                    Err(String::from("Interval must not be zero"))
+                                }
+                            })
+                    })
+                    .help("Time between polling the remote host"),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .long("format")
+                    .value_name("FORMAT")
+                    .possible_values(&["text", "json"])
+                    .case_insensitive(true)
+                    .default_value("text")
+                    .help("Format of events printed to stdout"),
+            )
+            .arg(
+                Arg::with_name("exec")
+                    .long("exec")
+                    .short("x")
+                    .alias("hook")
+                    .value_name("COMMAND")
+                    .env("FFSEND_WATCH_EXEC")
+                    .hide_env_values(true)
+                    .help("Run a shell command for each event")
+                    .long_help(
+                        "Run a shell command for each event, such as a desktop notification.\n\
+                         The event is described in environment variables:\n\
+                         FFSEND_EVENT: 'download' or 'expired'\n\
+                         FFSEND_ID, FFSEND_URL: the file ID and share URL without secret\n\
+                         FFSEND_LABEL: the file alias, name or share URL\n\
+                         FFSEND_DOWNLOADS, FFSEND_DOWNLOAD_LIMIT: download count and limit\
+                         ",
+                    ),
+            );
+
+        // With history support, allow watching all files from history
+        #[cfg(feature = "history")]
+        let cmd = cmd.arg(ArgUrl::build_required_unless(&["all"])).arg(
+            Arg::with_name("all")
+                .long("all")
+                .short("a")
+                .conflicts_with_all(&["URL", "owner"])
+                .help("Watch all files in history"),
+        );
+        #[cfg(not(feature = "history"))]
+        let cmd = cmd.arg(ArgUrl::build());
+
+        cmd
+    }
+}
@@ -10,6 +10,15 @@ pub const CLIENT_TIMEOUT: u64 = 30;
 /// Make sure this is big enough, or file uploads will be dropped. `0` to disable.
 pub const CLIENT_TRANSFER_TIMEOUT: u64 = 24 * 60 * 60;
 
+/// The number of times to retry a request that failed because of a transient network error.
+pub const CLIENT_RETRIES: u32 = 2;
+
+/// The time in seconds to wait before the first retry, doubled for each next retry.
+pub const CLIENT_RETRY_BACKOFF: u64 = 1;
+
+/// The time in seconds host limits are cached for, before fetching them from the host again.
+pub const HOST_LIMITS_CACHE_TTL: i64 = 24 * 60 * 60;
+
 /// The default desired version to select for the server API.
 pub const API_VERSION_DESIRED_DEFAULT: DesiredVersion = DesiredVersion::Assume(API_VERSION_ASSUME);
 
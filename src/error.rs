@@ -11,6 +11,12 @@ use crate::action::generate::completions::Error as CliGenerateCompletionsError;
 use crate::action::history::Error as CliHistoryError;
 use crate::action::info::Error as CliInfoError;
 use crate::action::upload::Error as CliUploadError;
+#[cfg(feature = "qrcode")]
+use crate::qrcode::Error as QrError;
+#[cfg(feature = "history")]
+use crate::queue::Error as QueueError;
+#[cfg(feature = "clipboard")]
+use crate::util::ClipboardError;
 
 #[derive(Fail, Debug)]
 pub enum Error {
@@ -45,6 +51,11 @@ impl From<ActionError> for Error {
 
 #[derive(Debug, Fail)]
 pub enum ActionError {
+    /// An error occurred while accessing the clipboard.
+    #[cfg(feature = "clipboard")]
+    #[fail(display = "failed to use the clipboard")]
+    Clipboard(#[cause] ClipboardError),
+
     /// An error occurred while invoking the delete action.
     #[fail(display = "failed to delete the file")]
     Delete(#[cause] DeleteError),
@@ -78,6 +89,16 @@ pub enum ActionError {
     #[fail(display = "failed to change the password")]
     Password(#[cause] PasswordError),
 
+    /// An error occurred while rendering a QR code.
+    #[cfg(feature = "qrcode")]
+    #[fail(display = "failed to render QR code")]
+    Qr(#[cause] QrError),
+
+    /// An error occurred while processing the upload queue.
+    #[cfg(feature = "history")]
+    #[fail(display = "failed to process the upload queue")]
+    Queue(#[cause] QueueError),
+
     /// An error occurred while invoking the version action.
     #[fail(display = "failed to determine server version")]
     Version(#[cause] VersionError),
@@ -92,6 +113,13 @@ pub enum ActionError {
     InvalidUrl(#[cause] FileParseError),
 }
 
+#[cfg(feature = "clipboard")]
+impl From<ClipboardError> for ActionError {
+    fn from(err: ClipboardError) -> ActionError {
+        ActionError::Clipboard(err)
+    }
+}
+
 impl From<DeleteError> for ActionError {
     fn from(err: DeleteError) -> ActionError {
         ActionError::Delete(err)
@@ -129,6 +157,20 @@ impl From<PasswordError> for ActionError {
     }
 }
 
+#[cfg(feature = "qrcode")]
+impl From<QrError> for ActionError {
+    fn from(err: QrError) -> ActionError {
+        ActionError::Qr(err)
+    }
+}
+
+#[cfg(feature = "history")]
+impl From<QueueError> for ActionError {
+    fn from(err: QueueError) -> ActionError {
+        ActionError::Queue(err)
+    }
+}
+
 impl From<VersionError> for ActionError {
     fn from(err: VersionError) -> ActionError {
         ActionError::Version(err)
@@ -1,7 +1,10 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
 use std::fs;
 use std::io::Error as IoError;
 use std::path::PathBuf;
 
+use chrono::{DateTime, Utc};
 use failure::Fail;
 use ffsend_api::{
     file::remote_file::{FileParseError, RemoteFile},
@@ -10,6 +13,8 @@ use ffsend_api::{
 use toml::{de::Error as DeError, ser::Error as SerError};
 use version_compare::Cmp;
 
+#[cfg(feature = "secret-store")]
+use crate::secret_store::Error as SecretStoreError;
 use crate::util::{print_error, print_warning};
 
 /// The minimum supported history file version.
@@ -27,6 +32,10 @@ pub struct History {
     /// The file history.
     files: Vec<RemoteFile>,
 
+    /// Local properties for files in the history, keyed by file ID.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    meta: HashMap<String, FileMeta>,
+
     /// Whether the list of files has changed.
     #[serde(skip)]
     changed: bool,
@@ -179,10 +188,11 @@ impl History {
             .map(|(i, _)| i)
             .collect();
 
-        // Remove these specific files
+        // Remove these specific files, and their local properties
         for i in expired_indices.iter().rev() {
             self.files.remove(*i);
         }
+        self.meta.remove(id);
 
         // Set the changed flag, and return
         if expired_indices.is_empty() {
@@ -203,6 +213,98 @@ impl History {
         &self.files
     }
 
+    /// Get all files, sorted by expiry time with the first expiring files last.
+    ///
+    /// This is the order files are listed in to the user, one-based indices given by the user
+    /// refer to this list.
+    pub fn files_sorted(&self) -> Vec<&RemoteFile> {
+        let mut files: Vec<&RemoteFile> = self.files.iter().collect();
+        files.sort_by_key(|f| Reverse(f.expire_at()));
+        files
+    }
+
+    /// Get the local properties for the file with the given ID, if any are known.
+    pub fn meta(&self, id: &str) -> Option<&FileMeta> {
+        self.meta.get(id)
+    }
+
+    /// Get the local properties for the file with the given ID, mutably.
+    ///
+    /// The properties are created if they don't exist yet. The history is marked as changed.
+    pub fn meta_mut(&mut self, id: &str) -> &mut FileMeta {
+        self.changed = true;
+        self.meta.entry(id.into()).or_default()
+    }
+
+    /// Get a label to show to the user for the given file.
+    ///
+    /// This is the alias or name of the file if known, or the share URL without secret.
+    /// The recipient is included if the share was created for one.
+    pub fn label(&self, file: &RemoteFile) -> String {
+        let meta = self.meta(file.id());
+        let label = meta
+            .and_then(|m| m.alias.as_ref().or(m.name.as_ref()))
+            .cloned()
+            .unwrap_or_else(|| file.download_url(false).into());
+        match meta.and_then(|m| m.recipient.as_ref()) {
+            Some(recipient) => format!("{} (for {})", label, recipient),
+            None => label,
+        }
+    }
+
+    /// Find a file in the history, by the given user reference.
+    ///
+    /// The reference is matched in the following order:
+    /// - one-based index as listed to the user, optionally prefixed with `#`
+    /// - user assigned alias
+    /// - file name
+    pub fn find(&self, reference: &str) -> Result<&RemoteFile, FindError> {
+        let reference = reference.trim();
+
+        // Match a one-based index
+        let index = reference.strip_prefix('#').unwrap_or(reference);
+        if let Ok(index) = index.parse::<usize>() {
+            return self
+                .files_sorted()
+                .into_iter()
+                .nth(index.wrapping_sub(1))
+                .ok_or(FindError::Index(index));
+        }
+
+        // Match an alias
+        if let Some(file) = self
+            .files
+            .iter()
+            .find(|f| self.meta(f.id()).and_then(|m| m.alias.as_deref()) == Some(reference))
+        {
+            return Ok(file);
+        }
+
+        // Match a file name, which must be unique
+        let named: Vec<&RemoteFile> = self
+            .files
+            .iter()
+            .filter(|f| self.meta(f.id()).and_then(|m| m.name.as_deref()) == Some(reference))
+            .collect();
+        match named.len() {
+            0 => Err(FindError::NotFound(reference.into())),
+            1 => Ok(named[0]),
+            n => Err(FindError::Ambiguous(reference.into(), n)),
+        }
+    }
+
+    /// Assign the given alias to the file with the given ID.
+    ///
+    /// Aliases are unique, if another file already uses this alias it is taken from it.
+    pub fn set_alias(&mut self, id: &str, alias: String) {
+        for meta in self.meta.values_mut() {
+            if meta.alias.as_ref() == Some(&alias) {
+                meta.alias = None;
+            }
+        }
+        self.meta_mut(id).alias = Some(alias);
+    }
+
     /// Get a file from the history, based on the given remote file.
     /// The file ID and host will be compared against all files in this history.
     /// If multiple files exist within the history that are equal, only one is returned.
@@ -213,10 +315,41 @@ impl History {
             .find(|f| f.id() == file.id() && f.host() == file.host())
     }
 
+    /// Clear the owner tokens of all files, after storing them elsewhere.
+    ///
+    /// The history is marked as changed if any token was cleared.
+    #[cfg(feature = "secret-store")]
+    pub fn clear_owner_tokens(&mut self) {
+        for file in &mut self.files {
+            if file.owner_token_mut().take().is_some() {
+                self.changed = true;
+            }
+        }
+    }
+
+    /// Fill in owner tokens of files that don't have one, using the given lookup function.
+    ///
+    /// Filled in tokens are only used in memory. Autosaving is disabled, and the history can't
+    /// be saved afterwards, to never write these tokens to the history file.
+    #[cfg(feature = "secret-store")]
+    pub fn fill_owner_tokens<F>(&mut self, mut lookup: F)
+    where
+        F: FnMut(&RemoteFile) -> Option<String>,
+    {
+        self.autosave = None;
+        for file in &mut self.files {
+            if !file.has_owner_token() {
+                let token = lookup(file);
+                file.set_owner_token(token);
+            }
+        }
+    }
+
     /// Clear all history.
     pub fn clear(&mut self) {
         self.changed = !self.files.is_empty();
         self.files.clear();
+        self.meta.clear();
     }
 
     /// Garbage collect (remove) all files that have been expired,
@@ -266,12 +399,37 @@ impl Default for History {
         Self {
             version: Some(crate_version!().into()),
             files: Vec::new(),
+            meta: HashMap::new(),
             changed: false,
             autosave: None,
         }
     }
 }
 
+/// Local properties of a file in the history, which the remote host doesn't know about.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FileMeta {
+    /// The name of the file, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// A user assigned alias to reference the file by.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    /// The SHA-256 checksum of the uploaded file, if computed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    /// The recipient this share was created for, if uploaded to multiple recipients.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+
+    /// The time to delete the file at, as scheduled by the user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Fail)]
 pub enum Error {
     /// An error occurred while loading the history from a file.
@@ -281,6 +439,11 @@ pub enum Error {
     /// An error occurred while saving the history to a file.
     #[fail(display = "failed to save history to file")]
     Save(#[cause] SaveError),
+
+    /// An error occurred while accessing owner tokens in the secret store.
+    #[cfg(feature = "secret-store")]
+    #[fail(display = "failed to access owner tokens in secret store")]
+    SecretStore(#[cause] SecretStoreError),
 }
 
 impl From<LoadError> for Error {
@@ -295,6 +458,13 @@ impl From<SaveError> for Error {
     }
 }
 
+#[cfg(feature = "secret-store")]
+impl From<SecretStoreError> for Error {
+    fn from(err: SecretStoreError) -> Self {
+        Error::SecretStore(err)
+    }
+}
+
 #[derive(Debug, Fail)]
 pub enum LoadError {
     /// Failed to read the file contents from the given file.
@@ -353,3 +523,21 @@ impl From<IoError> for SaveError {
         SaveError::Write(err)
     }
 }
+
+#[derive(Debug, Fail)]
+pub enum FindError {
+    /// No file exists at the given index.
+    #[fail(display = "no history entry at index #{}", _0)]
+    Index(usize),
+
+    /// No file matches the given alias or name.
+    #[fail(display = "no history entry with alias or name '{}'", _0)]
+    NotFound(String),
+
+    /// Multiple files have the given name.
+    #[fail(
+        display = "{} history entries are named '{}', use an index or alias instead",
+        _1, _0
+    )]
+    Ambiguous(String, usize),
+}
@@ -1,9 +1,17 @@
+#[cfg(feature = "secret-store")]
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use failure::Fail;
-use ffsend_api::file::remote_file::RemoteFile;
+use ffsend_api::{file::remote_file::RemoteFile, url::Url};
 
 use crate::cmd::matcher::MainMatcher;
-use crate::history::{Error as HistoryError, History};
-use crate::util::print_error;
+use crate::history::{Error as HistoryError, FileMeta, History};
+#[cfg(feature = "secret-store")]
+use crate::secret_store::{Backend, Error as SecretStoreError, SecretStore};
+#[cfg(feature = "secret-store")]
+use crate::util::print_warning;
+use crate::util::{bin_name, highlight, print_error, quit_error, ErrorHintsBuilder};
 
 /// Load the history from the given path, add the given file, and save it
 /// again.
@@ -13,9 +21,12 @@ use crate::util::print_error;
 /// overwrite properties in the already existing file when merging.
 ///
 /// If there is no file at the given path, new history will be created.
+///
+/// Any local properties set in `meta` are remembered for the file.
 fn add_error(
     matcher_main: &MainMatcher,
     file: RemoteFile,
+    meta: FileMeta,
     overwrite: bool,
 ) -> Result<(), HistoryError> {
     // Ignore if incognito
@@ -25,8 +36,83 @@ fn add_error(
 
     // Load the history, add the file, and save
     let mut history = History::load_or_new(matcher_main.history())?;
+    if meta.name.is_some()
+        || meta.alias.is_some()
+        || meta.sha256.is_some()
+        || meta.recipient.is_some()
+        || meta.delete_at.is_some()
+    {
+        let current = history.meta_mut(file.id());
+        current.name = meta.name.or_else(|| current.name.take());
+        current.alias = meta.alias.or_else(|| current.alias.take());
+        current.sha256 = meta.sha256.or_else(|| current.sha256.take());
+        current.recipient = meta.recipient.or_else(|| current.recipient.take());
+        current.delete_at = meta.delete_at.or_else(|| current.delete_at.take());
+    }
     history.add(file, overwrite);
-    history.save().map_err(|err| err.into())
+    save(matcher_main, &mut history)
+}
+
+/// Save the given history, and move its owner tokens into the secret store.
+///
+/// The history is saved before moving owner tokens, so the file and its owner token aren't lost if
+/// the secret store fails to open. Owner tokens that failed to move are kept in the history.
+#[allow(unused_variables)]
+fn save(matcher_main: &MainMatcher, history: &mut History) -> Result<(), HistoryError> {
+    history.save()?;
+
+    #[cfg(feature = "secret-store")]
+    {
+        match store_owner_tokens(matcher_main, history) {
+            Ok(true) => history.save()?,
+            Ok(false) => {}
+            Err(err) => print_warning(format!(
+                "failed to move owner tokens into the secret store, keeping them in history: {}",
+                err
+            )),
+        }
+    }
+
+    Ok(())
+}
+
+/// Move the owner tokens of all files in the given history into the secret store.
+///
+/// Tokens are only cleared from the history after they are stored. This also moves tokens left
+/// in the history before the secret store was used. Returns whether any token was moved, in which
+/// case the history must be saved again.
+#[cfg(feature = "secret-store")]
+fn store_owner_tokens(
+    matcher_main: &MainMatcher,
+    history: &mut History,
+) -> Result<bool, SecretStoreError> {
+    if matcher_main.secret_store() == Backend::History {
+        return Ok(false);
+    }
+
+    // Collect files having a token, open the store if there are any
+    let files: Vec<RemoteFile> = history
+        .files()
+        .iter()
+        .filter(|file| file.has_owner_token())
+        .cloned()
+        .collect();
+    if files.is_empty() {
+        return Ok(false);
+    }
+    let mut store = match SecretStore::open(matcher_main)? {
+        Some(store) => store,
+        None => return Ok(false),
+    };
+
+    // Store the tokens, then clear them from the history
+    for file in &files {
+        store.set(file, file.owner_token().unwrap())?;
+    }
+    store.retain(history.files());
+    store.save()?;
+    history.clear_owner_tokens();
+    Ok(true)
 }
 
 /// Load the history from the given path, add the given file, and save it
@@ -39,7 +125,32 @@ fn add_error(
 ///
 /// If an error occurred, the error is printed and ignored.
 pub fn add(matcher_main: &MainMatcher, file: RemoteFile, overwrite: bool) {
-    if let Err(err) = add_error(matcher_main, file, overwrite) {
+    add_meta(matcher_main, file, FileMeta::default(), overwrite)
+}
+
+/// Load the history from the given path, add the given file along with its name, and save it
+/// again.
+///
+/// See `add`, the file name allows the user to reference the file by it later.
+pub fn add_named(
+    matcher_main: &MainMatcher,
+    file: RemoteFile,
+    name: Option<String>,
+    overwrite: bool,
+) {
+    let meta = FileMeta {
+        name,
+        ..FileMeta::default()
+    };
+    add_meta(matcher_main, file, meta, overwrite)
+}
+
+/// Load the history from the given path, add the given file along with local properties, and
+/// save it again.
+///
+/// See `add`, properties set in `meta` are remembered for the file.
+pub fn add_meta(matcher_main: &MainMatcher, file: RemoteFile, meta: FileMeta, overwrite: bool) {
+    if let Err(err) = add_error(matcher_main, file, meta, overwrite) {
         print_error(err.context("failed to add file to local history, ignoring"));
     }
 }
@@ -47,7 +158,13 @@ pub fn add(matcher_main: &MainMatcher, file: RemoteFile, overwrite: bool) {
 /// Load the history from the given path, remove the given file by it's
 /// ID, and save it again.
 /// True is returned if any file was removed.
-fn remove_error(matcher_main: &MainMatcher, file: &RemoteFile) -> Result<bool, HistoryError> {
+///
+/// If `forget` is set, the owner token of the file is removed from the secret store as well.
+fn remove_error(
+    matcher_main: &MainMatcher,
+    file: &RemoteFile,
+    #[allow(unused)] forget: bool,
+) -> Result<bool, HistoryError> {
     // Ignore if incognito
     if matcher_main.incognito() {
         return Ok(false);
@@ -57,6 +174,15 @@ fn remove_error(matcher_main: &MainMatcher, file: &RemoteFile) -> Result<bool, H
     let mut history = History::load_or_new(matcher_main.history())?;
     let removed = history.remove(file.id());
     history.save()?;
+
+    // Forget the owner token in the secret store
+    #[cfg(feature = "secret-store")]
+    {
+        if removed && forget {
+            forget_owner_tokens_error(matcher_main, std::slice::from_ref(file))?;
+        }
+    }
+
     Ok(removed)
 }
 
@@ -64,7 +190,22 @@ fn remove_error(matcher_main: &MainMatcher, file: &RemoteFile) -> Result<bool, H
 /// ID, and save it again.
 /// True is returned if any file was removed.
 pub fn remove(matcher_main: &MainMatcher, file: &RemoteFile) -> bool {
-    let result = remove_error(matcher_main, file);
+    remove_meta(matcher_main, file, true)
+}
+
+/// Load the history from the given path, remove the given file by it's ID, and save it again,
+/// keeping its owner token in the secret store.
+///
+/// Use this in actions that don't use the owner token, so the secret store isn't accessed. The
+/// stale token is cleaned up when owner tokens are stored next.
+/// True is returned if any file was removed.
+pub fn remove_keep_token(matcher_main: &MainMatcher, file: &RemoteFile) -> bool {
+    remove_meta(matcher_main, file, false)
+}
+
+/// Remove the given file from the history, see `remove`.
+fn remove_meta(matcher_main: &MainMatcher, file: &RemoteFile, forget: bool) -> bool {
+    let result = remove_error(matcher_main, file, forget);
     let ok = result.is_ok();
     if let Err(err) = result {
         print_error(err.context("failed to remove file from local history, ignoring"));
@@ -72,6 +213,23 @@ pub fn remove(matcher_main: &MainMatcher, file: &RemoteFile) -> bool {
     ok
 }
 
+/// Load the history from the given path, schedule deleting the given file at the given time, and
+/// save it again.
+///
+/// The file is added to the history if it isn't in it yet. If `delete_at` is `None`, a scheduled
+/// deletion is cancelled.
+pub fn schedule_delete(
+    matcher_main: &MainMatcher,
+    file: RemoteFile,
+    delete_at: Option<DateTime<Utc>>,
+) -> Result<(), HistoryError> {
+    let mut history = History::load_or_new(matcher_main.history())?;
+    let id = file.id().to_owned();
+    history.add(file, true);
+    history.meta_mut(&id).delete_at = delete_at;
+    save(matcher_main, &mut history)
+}
+
 /// Derive the file secret and owner token from the history for the given file.
 /// The newly derived properties will be set into the given borrowed remote file.
 /// This method may be used to automatically derive the properties for some file actions
@@ -85,6 +243,7 @@ pub fn remove(matcher_main: &MainMatcher, file: &RemoteFile) -> bool {
 /// and no properties could be derived, `false` is returned.
 /// Incognito mode does not have any effect on this method,
 /// as it won't ever change the history.
+/// Owner tokens kept in the secret store are not derived, see `derive_owner_token`.
 ///
 /// If any property was successfully derived, `true` is returned.
 pub fn derive_file_properties(matcher_main: &MainMatcher, file: &mut RemoteFile) -> bool {
@@ -121,3 +280,188 @@ pub fn derive_file_properties(matcher_main: &MainMatcher, file: &mut RemoteFile)
         None => false,
     }
 }
+
+/// Derive the owner token for the given file from the secret store, if it isn't set yet.
+///
+/// Only use this in actions that use the owner token, as opening a store file may prompt for its
+/// password. Returns whether an owner token was derived.
+#[cfg(feature = "secret-store")]
+pub fn derive_owner_token(matcher_main: &MainMatcher, file: &mut RemoteFile) -> bool {
+    if file.has_owner_token() {
+        return false;
+    }
+    let token = stored_owner_tokens(matcher_main, &[&*file])
+        .into_iter()
+        .next()
+        .flatten();
+    let derived = token.is_some();
+    if derived {
+        file.set_owner_token(token);
+    }
+    derived
+}
+
+/// Get the owner tokens for the given files from the secret store.
+///
+/// A token is returned for each file, `None` if the store has no token for it.
+/// If the store could not be accessed, the error is printed and no tokens are returned.
+#[cfg(feature = "secret-store")]
+fn stored_owner_tokens(matcher_main: &MainMatcher, files: &[&RemoteFile]) -> Vec<Option<String>> {
+    let store = match SecretStore::open_existing(matcher_main) {
+        Ok(Some(store)) => store,
+        Ok(None) => return vec![None; files.len()],
+        Err(err) => {
+            print_error(err.context("failed to get owner tokens from secret store, ignoring"));
+            return vec![None; files.len()];
+        }
+    };
+
+    files
+        .iter()
+        .map(|file| match store.get(file) {
+            Ok(token) => token,
+            Err(err) => {
+                print_error(err.context("failed to get owner token from secret store, ignoring"));
+                None
+            }
+        })
+        .collect()
+}
+
+/// Remove the owner tokens of the given files from the secret store.
+#[cfg(feature = "secret-store")]
+fn forget_owner_tokens_error(
+    matcher_main: &MainMatcher,
+    files: &[RemoteFile],
+) -> Result<(), HistoryError> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    if let Some(mut store) = SecretStore::open_existing(matcher_main)? {
+        for file in files {
+            store.remove(file)?;
+        }
+        store.save()?;
+    }
+    Ok(())
+}
+
+/// Remove the owner tokens of the given files from the secret store.
+///
+/// This should be used when removing files from the history.
+/// If an error occurred, the error is printed and ignored.
+#[cfg(feature = "secret-store")]
+pub fn forget_owner_tokens(matcher_main: &MainMatcher, files: &[RemoteFile]) {
+    if let Err(err) = forget_owner_tokens_error(matcher_main, files) {
+        print_error(err.context("failed to remove owner tokens from secret store, ignoring"));
+    }
+}
+
+/// Fill in owner tokens from the secret store for files in the given history.
+///
+/// The tokens are only used in memory, the history can't be saved afterwards.
+#[cfg(feature = "secret-store")]
+pub fn fill_owner_tokens(matcher_main: &MainMatcher, history: &mut History) {
+    let files: Vec<&RemoteFile> = history
+        .files()
+        .iter()
+        .filter(|file| !file.has_owner_token())
+        .collect();
+    if files.is_empty() || matcher_main.secret_store() == Backend::History {
+        return;
+    }
+    let tokens: HashMap<String, String> = files
+        .iter()
+        .zip(stored_owner_tokens(matcher_main, &files))
+        .filter_map(|(file, token)| Some((file.id().to_owned(), token?)))
+        .collect();
+    history.fill_owner_tokens(|file| tokens.get(file.id()).cloned());
+}
+
+/// Look up the given file in the history, and get its history entry and local properties.
+///
+/// `None` is returned if the file isn't in the history.
+/// If the history could not be loaded, the error is printed and `None` is returned.
+/// Incognito mode does not have any effect on this method,
+/// as it won't ever change the history.
+pub fn lookup(
+    matcher_main: &MainMatcher,
+    file: &RemoteFile,
+) -> Option<(RemoteFile, Option<FileMeta>)> {
+    let history = match History::load_or_new(matcher_main.history()) {
+        Ok(history) => history,
+        Err(err) => {
+            print_error(err.context("failed to look up file in history, ignoring"));
+            return None;
+        }
+    };
+
+    history
+        .get_file(file)
+        .map(|entry| (entry.clone(), history.meta(file.id()).cloned()))
+}
+
+/// Get the time the given file was uploaded at, if known.
+///
+/// The upload time isn't exposed by `RemoteFile`, so it is read from its serialized form.
+pub fn upload_at(file: &RemoteFile) -> Option<DateTime<Utc>> {
+    let value = serde_json::to_value(file).ok()?;
+    let upload_at = value.get("upload_at")?.as_str()?;
+    DateTime::parse_from_rfc3339(upload_at)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Load the history from the given path.
+/// If there is no file at the given path, new history will be created.
+///
+/// If the history could not be loaded, the program will quit with an error message.
+pub fn load(matcher_main: &MainMatcher) -> History {
+    match History::load_or_new(matcher_main.history()) {
+        Ok(history) => history,
+        Err(err) => quit_error(
+            err.context("failed to load file history"),
+            ErrorHintsBuilder::default()
+                .history(true)
+                .verbose(false)
+                .build()
+                .unwrap(),
+        ),
+    }
+}
+
+/// Load the history from the given path, including owner tokens.
+///
+/// Like `load`, but owner tokens kept in the secret store are filled in.
+/// These are only used in memory, the returned history can't be saved.
+pub fn load_with_owner_tokens(matcher_main: &MainMatcher) -> History {
+    #[allow(unused_mut)]
+    let mut history = load(matcher_main);
+    #[cfg(feature = "secret-store")]
+    fill_owner_tokens(matcher_main, &mut history);
+    history
+}
+
+/// Resolve the share URL of a file in the history, by the given user reference.
+///
+/// The reference may be an index, alias or file name, see `History::find`.
+/// Incognito mode does not have any effect on this method, as it won't ever change the history.
+///
+/// If no file matches, the program will quit with an error message.
+pub fn resolve_url(matcher_main: &MainMatcher, reference: &str) -> Url {
+    // Find the file, return its share URL
+    match load(matcher_main).find(reference) {
+        Ok(file) => file.download_url(true),
+        Err(err) => quit_error(
+            err.context("failed to resolve file from history"),
+            ErrorHintsBuilder::default()
+                .add_info(format!(
+                    "Use '{}' to list history entries",
+                    highlight(&format!("{} history", bin_name()))
+                ))
+                .verbose(false)
+                .build()
+                .unwrap(),
+        ),
+    }
+}
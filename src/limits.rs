@@ -0,0 +1,379 @@
+//! Upload limits of Send hosts.
+//!
+//! Hosts publish their configuration, including the maximum file size and the allowed download
+//! limits and expiry times, at the `/config` endpoint. Limits the host doesn't publish fall back
+//! to the defaults for the API version.
+//!
+//! Fetched limits are cached per host, see `HOST_LIMITS_CACHE_TTL`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Error as IoError;
+use std::path::PathBuf;
+
+use chrono::{Duration, Utc};
+use failure::Fail;
+use ffsend_api::{
+    api::{
+        request::{ensure_success, ResponseError},
+        Version as ApiVersion,
+    },
+    client::Client,
+    config, reqwest,
+    url::Url,
+};
+
+use crate::cmd::matcher::MainMatcher;
+use crate::config::{API_VERSION_ASSUME, HOST_LIMITS_CACHE_TTL};
+use crate::util::{app_project_dirs, format_duration, print_error};
+
+/// The host configuration endpoint.
+const CONFIG_ENDPOINT: &str = "config";
+
+lazy_static! {
+    /// The default download limits, to offer in generated shell completions.
+    static ref COMPLETION_DOWNLOADS: Vec<String> =
+        Limits::default_for(API_VERSION_ASSUME, false)
+            .downloads
+            .iter()
+            .map(|downloads| downloads.to_string())
+            .collect();
+
+    /// The default expiry times, to offer in generated shell completions.
+    static ref COMPLETION_EXPIRY_TIMES: Vec<String> =
+        Limits::default_for(API_VERSION_ASSUME, false)
+            .expiry_times
+            .iter()
+            .map(|time| format_duration(Duration::seconds(*time as i64)))
+            .collect();
+}
+
+/// Upload limits of a host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Limits {
+    /// The maximum file size in bytes.
+    pub size_max: u64,
+
+    /// The allowed download limits.
+    pub downloads: Vec<usize>,
+
+    /// The default download limit.
+    pub downloads_default: usize,
+
+    /// The maximum expiry time in seconds.
+    pub expiry_max: usize,
+
+    /// The suggested expiry times in seconds.
+    #[serde(default)]
+    pub expiry_times: Vec<usize>,
+
+    /// Whether the limits were obtained from the host.
+    pub from_host: bool,
+}
+
+impl Limits {
+    /// The default limits for the given API version.
+    pub fn default_for(api_version: ApiVersion, auth: bool) -> Self {
+        Self {
+            size_max: config::upload_size_max(api_version, auth),
+            downloads: config::downloads_max(api_version, auth).to_vec(),
+            downloads_default: config::downloads_default(api_version, auth),
+            expiry_max: *config::expiry_max(api_version, auth)
+                .iter()
+                .max()
+                .expect("no expiry times for API version"),
+            expiry_times: config::expiry_max(api_version, auth).to_vec(),
+            from_host: false,
+        }
+    }
+
+    /// Get the limits for the given host.
+    ///
+    /// Cached limits are used if not expired. Otherwise the limits are fetched from the host and
+    /// cached, the defaults for the API version are used if that fails.
+    pub fn for_host(
+        matcher_main: &MainMatcher,
+        client: &Client,
+        host: &Url,
+        api_version: ApiVersion,
+        auth: bool,
+    ) -> Self {
+        // Use the cached limits if available
+        let mut cache = LimitsCache::load();
+        if let Some(limits) = cache.get(host) {
+            return limits;
+        }
+
+        match Self::fetch(client, host, api_version, auth) {
+            Ok(limits) => {
+                cache.set(host, limits.clone());
+                if let Err(err) = cache.save() {
+                    if matcher_main.verbose() {
+                        print_error(err.context("failed to cache host limits, ignoring"));
+                    }
+                }
+                limits
+            }
+            Err(err) => {
+                if matcher_main.verbose() {
+                    print_error(err.context("failed to fetch host limits, using defaults"));
+                }
+                Self::default_for(api_version, auth)
+            }
+        }
+    }
+
+    /// Fetch the limits from the given host.
+    ///
+    /// Limits the host doesn't publish fall back to the defaults for the given API version.
+    pub fn fetch(
+        client: &Client,
+        host: &Url,
+        api_version: ApiVersion,
+        auth: bool,
+    ) -> Result<Self, Error> {
+        let url = host.join(CONFIG_ENDPOINT).map_err(|_| Error::Url)?;
+        let response = client.get(url).send().map_err(Error::Request)?;
+        ensure_success(&response)?;
+        let config: HostConfig = response.json().map_err(Error::Malformed)?;
+        Ok(Self::from_config(config, api_version, auth))
+    }
+
+    /// Build the limits from the given host configuration.
+    fn from_config(config: HostConfig, api_version: ApiVersion, auth: bool) -> Self {
+        let mut limits = Self::default_for(api_version, auth);
+        limits.from_host = true;
+
+        // Use anonymous limits if the host differentiates, as we don't authenticate
+        let host_limits = config.limits.anon.as_deref().unwrap_or(&config.limits);
+
+        if let Some(size_max) = host_limits.max_file_size {
+            limits.size_max = size_max;
+        }
+
+        // Select the allowed download limits
+        if let Some(counts) = config
+            .defaults
+            .download_counts
+            .as_ref()
+            .filter(|c| !c.is_empty())
+        {
+            limits.downloads = counts.clone();
+        }
+
+        // The API can't set download limits above 255, ignore higher limits of the host
+        let max = host_limits
+            .max_downloads
+            .map_or(u8::MAX as usize, |max| max.min(u8::MAX as usize));
+        limits.downloads.retain(|count| *count <= max);
+        if limits.downloads.is_empty() {
+            limits.downloads.push(max);
+        }
+        if let Some(default) = config.defaults.downloads {
+            limits.downloads_default = default;
+        }
+
+        // Select the maximum expiry time
+        if let Some(times) = config
+            .defaults
+            .expire_times_seconds
+            .as_ref()
+            .filter(|t| !t.is_empty())
+        {
+            limits.expiry_times = times.clone();
+        }
+        if let Some(max) = host_limits.max_expire_seconds.or_else(|| {
+            config
+                .defaults
+                .expire_times_seconds
+                .as_ref()
+                .and_then(|times| times.iter().max().copied())
+        }) {
+            limits.expiry_max = max;
+        }
+
+        limits
+    }
+
+    /// Get the download limits to offer in generated shell completions.
+    ///
+    /// These are the defaults for the assumed API version, so completions are the same
+    /// everywhere and are generated without network access.
+    pub fn completion_downloads() -> Vec<&'static str> {
+        COMPLETION_DOWNLOADS.iter().map(|d| d.as_str()).collect()
+    }
+
+    /// Get the expiry times to offer in generated shell completions.
+    ///
+    /// See `completion_downloads`.
+    pub fn completion_expiry_times() -> Vec<&'static str> {
+        COMPLETION_EXPIRY_TIMES.iter().map(|t| t.as_str()).collect()
+    }
+
+    /// Get the allowed download limits formatted as list.
+    pub fn downloads_list(&self) -> String {
+        self.downloads
+            .iter()
+            .map(|value| format!("{}", value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Get the suggested expiry times formatted as list.
+    pub fn expiry_list(&self) -> String {
+        self.expiry_times
+            .iter()
+            .filter(|time| **time <= self.expiry_max)
+            .map(|time| format_duration(Duration::seconds(*time as i64)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// A cache of host limits, keyed by host.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LimitsCache {
+    /// The cached limits by host URL.
+    #[serde(default)]
+    hosts: HashMap<String, CachedLimits>,
+}
+
+/// Cached limits for a host.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedLimits {
+    /// The time the limits were fetched at, as UNIX timestamp.
+    fetched_at: i64,
+
+    /// The limits.
+    limits: Limits,
+}
+
+impl LimitsCache {
+    /// The path of the cache file.
+    fn path() -> PathBuf {
+        app_project_dirs().cache_dir().join("host-limits.toml")
+    }
+
+    /// Load the cache, an empty cache is returned if it doesn't exist or is invalid.
+    fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|data| toml::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the cache.
+    fn save(&self) -> Result<(), CacheError> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(CacheError::Write)?;
+        }
+        let data = toml::to_string(self).map_err(CacheError::Serialize)?;
+        fs::write(path, data).map_err(CacheError::Write)
+    }
+
+    /// Get the cached limits for the given host, if not expired.
+    fn get(&self, host: &Url) -> Option<Limits> {
+        self.hosts
+            .get(host.as_str())
+            .filter(|cached| Utc::now().timestamp() - cached.fetched_at < HOST_LIMITS_CACHE_TTL)
+            .map(|cached| cached.limits.clone())
+    }
+
+    /// Set the limits for the given host.
+    fn set(&mut self, host: &Url, limits: Limits) {
+        self.hosts.insert(
+            host.as_str().into(),
+            CachedLimits {
+                fetched_at: Utc::now().timestamp(),
+                limits,
+            },
+        );
+    }
+}
+
+/// The host configuration, as published by the host.
+#[derive(Debug, Default, Deserialize)]
+struct HostConfig {
+    /// The host upload limits.
+    #[serde(rename = "LIMITS", default)]
+    limits: HostLimits,
+
+    /// The host upload defaults.
+    #[serde(rename = "DEFAULTS", default)]
+    defaults: HostDefaults,
+}
+
+/// Upload limits in the host configuration.
+#[derive(Debug, Default, Deserialize)]
+struct HostLimits {
+    /// Limits for anonymous uploads, used by older hosts.
+    #[serde(rename = "ANON")]
+    anon: Option<Box<HostLimits>>,
+
+    /// The maximum file size in bytes.
+    #[serde(rename = "MAX_FILE_SIZE")]
+    max_file_size: Option<u64>,
+
+    /// The maximum download limit.
+    #[serde(rename = "MAX_DOWNLOADS")]
+    max_downloads: Option<usize>,
+
+    /// The maximum expiry time in seconds.
+    #[serde(rename = "MAX_EXPIRE_SECONDS")]
+    max_expire_seconds: Option<usize>,
+}
+
+/// Upload defaults in the host configuration.
+#[derive(Debug, Default, Deserialize)]
+struct HostDefaults {
+    /// The default download limit.
+    #[serde(rename = "DOWNLOADS")]
+    downloads: Option<usize>,
+
+    /// The download limits to choose from.
+    #[serde(rename = "DOWNLOAD_COUNTS")]
+    download_counts: Option<Vec<usize>>,
+
+    /// The expiry times to choose from, in seconds.
+    #[serde(rename = "EXPIRE_TIMES_SECONDS")]
+    expire_times_seconds: Option<Vec<usize>>,
+}
+
+/// A host limits error.
+#[derive(Debug, Fail)]
+pub enum Error {
+    /// Failed to build the configuration URL for the host.
+    #[fail(display = "invalid host configuration URL")]
+    Url,
+
+    /// Failed to send the configuration request.
+    #[fail(display = "failed to request host configuration")]
+    Request(#[cause] reqwest::Error),
+
+    /// The server responded with a bad response.
+    #[fail(display = "failed to fetch host configuration, got bad response")]
+    Response(#[cause] ResponseError),
+
+    /// The host configuration is malformed.
+    #[fail(display = "host configuration is malformed")]
+    Malformed(#[cause] reqwest::Error),
+}
+
+impl From<ResponseError> for Error {
+    fn from(err: ResponseError) -> Self {
+        Error::Response(err)
+    }
+}
+
+/// A host limits cache error.
+#[derive(Debug, Fail)]
+pub enum CacheError {
+    /// Failed to serialize the cache.
+    #[fail(display = "failed to serialize host limits cache")]
+    Serialize(#[cause] toml::ser::Error),
+
+    /// Failed to write the cache file.
+    #[fail(display = "failed to write host limits cache file")]
+    Write(#[cause] IoError),
+}
@@ -0,0 +1,134 @@
+//! Sending share URLs by email.
+//!
+//! Messages are sent through an SMTP relay if configured, or through the local `sendmail`
+//! binary otherwise.
+
+use std::path::PathBuf;
+
+use failure::Fail;
+use lettre::{
+    address::AddressError,
+    message::{header::ContentType, Mailbox},
+    transport::{sendmail::Error as SendmailError, smtp::Error as SmtpError},
+    Message, SendmailTransport, SmtpTransport, Transport as _,
+};
+
+use crate::message::ShareInfo;
+
+/// The default subject template.
+pub const SUBJECT_DEFAULT: &str = "File shared with you: {name}";
+
+/// The default body template.
+pub const BODY_DEFAULT: &str = "A file has been shared with you.
+
+Name: {name}
+Size: {size}
+Expires in: {expiry}
+Download limit: {downloads}
+
+{url}
+";
+
+/// The subject template for the separate password message.
+const PASSWORD_SUBJECT: &str = "Password for {name}";
+
+/// The body template for the separate password message.
+const PASSWORD_BODY: &str = "The password for {name} is:\n\n{password}\n";
+
+/// How to send mail.
+#[derive(Debug, Clone)]
+pub enum Transport {
+    /// Through an SMTP relay, given as connection URL.
+    Smtp(String),
+
+    /// Through a `sendmail` binary, the one in `PATH` if not set.
+    Sendmail(Option<PathBuf>),
+}
+
+/// What and how to mail.
+#[derive(Debug, Clone)]
+pub struct Mail {
+    /// The sender.
+    pub from: Mailbox,
+
+    /// The recipients.
+    pub to: Vec<Mailbox>,
+
+    /// The subject template.
+    pub subject: String,
+
+    /// The body template.
+    pub body: String,
+
+    /// Whether to send the password in a separate message.
+    pub password: bool,
+
+    /// The transport to send through.
+    pub transport: Transport,
+}
+
+impl Mail {
+    /// Mail the given shared file.
+    ///
+    /// If requested and the file has a password, it is sent in a separate message.
+    pub fn send(&self, info: &ShareInfo) -> Result<(), Error> {
+        self.send_message(info.render(&self.subject), info.render(&self.body))?;
+        if self.password && info.password.is_some() {
+            self.send_message(info.render(PASSWORD_SUBJECT), info.render(PASSWORD_BODY))?;
+        }
+        Ok(())
+    }
+
+    /// Build and send a single plain text message to all recipients.
+    fn send_message(&self, subject: String, body: String) -> Result<(), Error> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        let message = builder.body(body).map_err(Error::Build)?;
+
+        match &self.transport {
+            Transport::Smtp(url) => SmtpTransport::from_url(url)
+                .map_err(Error::Smtp)?
+                .build()
+                .send(&message)
+                .map(drop)
+                .map_err(Error::Smtp),
+            Transport::Sendmail(command) => command
+                .as_ref()
+                .map(SendmailTransport::new_with_command)
+                .unwrap_or_else(SendmailTransport::new)
+                .send(&message)
+                .map_err(Error::Sendmail),
+        }
+    }
+}
+
+/// Parse the given email address.
+pub fn parse_address(address: &str) -> Result<Mailbox, AddressError> {
+    address.trim().parse()
+}
+
+/// Check whether the given SMTP relay connection URL is valid.
+pub fn check_smtp_url(url: &str) -> Result<(), SmtpError> {
+    SmtpTransport::from_url(url).map(drop)
+}
+
+/// A mail error.
+#[derive(Debug, Fail)]
+pub enum Error {
+    /// Failed to build the message.
+    #[fail(display = "failed to build email message")]
+    Build(#[cause] lettre::error::Error),
+
+    /// Failed to send the message through the SMTP relay.
+    #[fail(display = "failed to send email through SMTP relay")]
+    Smtp(#[cause] SmtpError),
+
+    /// Failed to send the message through sendmail.
+    #[fail(display = "failed to send email through sendmail")]
+    Sendmail(#[cause] SendmailError),
+}
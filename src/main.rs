@@ -6,7 +6,6 @@ extern crate derive_builder;
 extern crate failure;
 #[macro_use]
 extern crate lazy_static;
-#[cfg(feature = "history")]
 #[macro_use]
 extern crate serde_derive;
 
@@ -22,13 +21,29 @@ mod history;
 #[cfg(feature = "history")]
 mod history_tool;
 mod host;
+mod limits;
+#[cfg(feature = "mail")]
+mod mail;
+mod manifest;
+mod message;
+mod passphrase;
+mod password_policy;
 mod progress;
+#[cfg(feature = "qrcode")]
+mod qrcode;
+#[cfg(feature = "history")]
+mod queue;
+mod retry;
+#[cfg(feature = "secret-store")]
+mod secret_store;
 #[cfg(feature = "urlshorten")]
 mod urlshorten;
 mod util;
 
 use std::process;
 
+#[cfg(feature = "clipboard")]
+use crate::action::clear_clipboard::ClearClipboard;
 use crate::action::debug::Debug;
 use crate::action::delete::Delete;
 use crate::action::download::Download;
@@ -39,8 +54,17 @@ use crate::action::history::History;
 use crate::action::info::Info;
 use crate::action::params::Params;
 use crate::action::password::Password;
+#[cfg(feature = "qrcode")]
+use crate::action::qr::Qr;
+#[cfg(feature = "history")]
+use crate::action::queue::Queue;
+#[cfg(feature = "history")]
+use crate::action::reap::Reap;
+#[cfg(feature = "history")]
+use crate::action::schedule_delete::ScheduleDelete;
 use crate::action::upload::Upload;
 use crate::action::version::Version;
+use crate::action::watch::Watch;
 use crate::cmd::{
     matcher::{MainMatcher, Matcher},
     Handler,
@@ -67,6 +91,16 @@ fn main() {
 /// If no proper action is selected, the program will quit with an error
 /// message.
 fn invoke_action(handler: &Handler) -> Result<(), Error> {
+    // Match the clear clipboard command
+    #[cfg(feature = "clipboard")]
+    {
+        if handler.clear_clipboard().is_some() {
+            return ClearClipboard::new(handler.matches())
+                .invoke()
+                .map_err(|err| err.into());
+        }
+    }
+
     // Match the debug command
     if handler.debug().is_some() {
         return Debug::new(handler.matches())
@@ -133,6 +167,46 @@ fn invoke_action(handler: &Handler) -> Result<(), Error> {
             .map_err(|err| err.into());
     }
 
+    // Match the QR code command
+    #[cfg(feature = "qrcode")]
+    {
+        if handler.qr().is_some() {
+            return Qr::new(handler.matches())
+                .invoke()
+                .map_err(|err| err.into());
+        }
+    }
+
+    // Match the queue command
+    #[cfg(feature = "history")]
+    {
+        if handler.queue().is_some() {
+            return Queue::new(handler.matches())
+                .invoke()
+                .map_err(|err| err.into());
+        }
+    }
+
+    // Match the reap command
+    #[cfg(feature = "history")]
+    {
+        if handler.reap().is_some() {
+            return Reap::new(handler.matches())
+                .invoke()
+                .map_err(|err| err.into());
+        }
+    }
+
+    // Match the schedule delete command
+    #[cfg(feature = "history")]
+    {
+        if handler.schedule_delete().is_some() {
+            return ScheduleDelete::new(handler.matches())
+                .invoke()
+                .map_err(|err| err.into());
+        }
+    }
+
     // Match the upload command
     if handler.upload().is_some() {
         return Upload::new(handler.matches())
@@ -147,6 +221,13 @@ fn invoke_action(handler: &Handler) -> Result<(), Error> {
             .map_err(|err| err.into());
     }
 
+    // Match the watch downloads command
+    if handler.watch().is_some() {
+        return Watch::new(handler.matches())
+            .invoke()
+            .map_err(|err| err.into());
+    }
+
     // Get the main matcher
     let matcher_main = MainMatcher::with(handler.matches()).unwrap();
 
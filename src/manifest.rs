@@ -0,0 +1,204 @@
+//! Manifests for files that are split across multiple shares.
+//!
+//! Large files may be split into parts, each uploaded as separate share. A manifest listing the
+//! share URL, size and checksum of each part is uploaded as share of its own, to download, verify
+//! and reassemble the original file from.
+
+use std::fs::{self, File};
+use std::io::{self, Error as IoError, Read, Write};
+use std::path::{Path, PathBuf};
+
+use failure::Fail;
+use serde_json::{Error as JsonError, Value};
+use sha2::{Digest, Sha256};
+
+use crate::util::hex_string;
+
+/// The file name suffix used for manifest shares.
+pub const MANIFEST_SUFFIX: &str = ".ffsend-manifest.json";
+
+/// The format identifier every manifest carries, to recognize manifests by their content.
+const MANIFEST_FORMAT: &str = "ffsend-manifest";
+
+/// The current manifest format version.
+const MANIFEST_VERSION: u32 = 1;
+
+/// The maximum size in bytes of a share to consider as manifest.
+const MANIFEST_MAX_SIZE: u64 = 1024 * 1024;
+
+/// A manifest describing a file split across multiple shares.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The manifest format identifier, always `MANIFEST_FORMAT`.
+    format: String,
+
+    /// The manifest format version.
+    version: u32,
+
+    /// The name of the original file.
+    pub name: String,
+
+    /// The size of the original file in bytes.
+    pub size: u64,
+
+    /// The SHA-256 checksum of the original file.
+    pub sha256: String,
+
+    /// The parts of the file, in order.
+    pub parts: Vec<Part>,
+}
+
+/// A part of a split file, uploaded as separate share.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Part {
+    /// The share URL of the part, including the secret.
+    pub url: String,
+
+    /// The size of the part in bytes.
+    pub size: u64,
+
+    /// The SHA-256 checksum of the part.
+    pub sha256: String,
+}
+
+impl Manifest {
+    /// Construct a new manifest for the given original file.
+    pub fn new(name: String, size: u64, sha256: String, parts: Vec<Part>) -> Self {
+        Self {
+            format: MANIFEST_FORMAT.into(),
+            version: MANIFEST_VERSION,
+            name,
+            size,
+            sha256,
+            parts,
+        }
+    }
+
+    /// Check whether a share with the given file name and size may be a manifest share.
+    ///
+    /// This is only a hint, the share is a manifest if its contents load successfully.
+    pub fn is_candidate(name: &str, size: u64) -> bool {
+        name.ends_with(MANIFEST_SUFFIX) && size <= MANIFEST_MAX_SIZE
+    }
+
+    /// Get the name of the manifest share for the original file with the given name.
+    pub fn manifest_name(name: &str) -> String {
+        format!("{}{}", name, MANIFEST_SUFFIX)
+    }
+
+    /// Load a manifest from the given file.
+    ///
+    /// Returns `Error::NotManifest` if the file does not carry the manifest format identifier.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let data = fs::read(path).map_err(Error::Read)?;
+        let value: Value = serde_json::from_slice(&data).map_err(|_| Error::NotManifest)?;
+        if value.get("format").and_then(Value::as_str) != Some(MANIFEST_FORMAT) {
+            return Err(Error::NotManifest);
+        }
+        let manifest: Self = serde_json::from_value(value).map_err(Error::Parse)?;
+        if manifest.version > MANIFEST_VERSION {
+            return Err(Error::Version(manifest.version));
+        }
+        Ok(manifest)
+    }
+
+    /// Save the manifest to the given file.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let data = serde_json::to_vec_pretty(self).map_err(Error::Parse)?;
+        fs::write(path, data).map_err(Error::Write)
+    }
+}
+
+/// A part file of a split file, see `split`.
+pub struct PartFile {
+    /// The path of the part file.
+    pub path: PathBuf,
+
+    /// The size of the part in bytes.
+    pub size: u64,
+
+    /// The SHA-256 checksum of the part, as lowercase hexadecimal string.
+    pub sha256: String,
+}
+
+/// Split the file at the given `path` into parts of at most `part_size` bytes.
+///
+/// The parts are written to the given directory, and are returned in order along with the
+/// SHA-256 checksum of the whole file. Checksums are computed while splitting, so the file is
+/// read once.
+pub fn split(path: &Path, part_size: u64, dir: &Path) -> Result<(Vec<PartFile>, String), Error> {
+    let mut file = File::open(path).map_err(Error::Read)?;
+    let size = file.metadata().map_err(Error::Read)?.len();
+
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    let mut parts = Vec::new();
+    let mut remaining = size;
+    while remaining > 0 {
+        let part_path = dir.join(format!("part{}", parts.len() + 1));
+        let mut part = File::create(&part_path).map_err(Error::Write)?;
+        let mut part_hasher = Sha256::new();
+        let mut copied = 0;
+        while copied < part_size {
+            let len = buf.len().min((part_size - copied) as usize);
+            let read = file.read(&mut buf[..len]).map_err(Error::Read)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+            part_hasher.update(&buf[..read]);
+            part.write_all(&buf[..read]).map_err(Error::Write)?;
+            copied += read as u64;
+        }
+        remaining = remaining.saturating_sub(copied);
+        parts.push(PartFile {
+            path: part_path,
+            size: copied,
+            sha256: hex_string(&part_hasher.finalize()),
+        });
+
+        // Stop if the file was truncated while splitting
+        if copied == 0 {
+            break;
+        }
+    }
+
+    Ok((parts, hex_string(&hasher.finalize())))
+}
+
+/// Join the given part files into the file at the given `path`, in order.
+pub fn join(parts: &[PathBuf], path: &Path) -> Result<(), Error> {
+    let mut file = File::create(path).map_err(Error::Write)?;
+    for part in parts {
+        let mut part = File::open(part).map_err(Error::Read)?;
+        io::copy(&mut part, &mut file).map_err(Error::Write)?;
+    }
+    file.flush().map_err(Error::Write)
+}
+
+/// A manifest or file splitting error.
+#[derive(Debug, Fail)]
+pub enum Error {
+    /// Failed to read a file.
+    #[fail(display = "failed to read file")]
+    Read(#[cause] IoError),
+
+    /// Failed to write a file.
+    #[fail(display = "failed to write file")]
+    Write(#[cause] IoError),
+
+    /// Failed to parse or serialize the manifest.
+    #[fail(display = "invalid manifest")]
+    Parse(#[cause] JsonError),
+
+    /// The file is not a manifest.
+    #[fail(display = "file is not a manifest")]
+    NotManifest,
+
+    /// The manifest has an unsupported format version.
+    #[fail(
+        display = "manifest version {} is not supported, update ffsend to download this file",
+        _0
+    )]
+    Version(u32),
+}
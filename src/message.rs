@@ -0,0 +1,241 @@
+//! Share messages rendered from templates.
+//!
+//! Templates may contain placeholders such as `{url}` and `{name}`, which are replaced with
+//! properties of the shared file. Unknown placeholders are kept as is. The escape sequences `\n`
+//! and `\t` are replaced with a newline and tab, to allow multi line templates on the command
+//! line.
+//!
+//! Named templates may be configured in the `messages.toml` file in the configuration
+//! directory, these override the built-in templates:
+//!
+//! ```toml
+//! [templates]
+//! team = "New upload: {url} (password: {password})"
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Duration;
+use ffsend_api::url::Url;
+use regex::{Captures, Regex};
+
+#[cfg(feature = "clipboard")]
+use crate::util::bin_name;
+use crate::util::{
+    app_project_dirs, format_bytes, format_duration, quit_error_msg, ErrorHintsBuilder,
+};
+
+/// Built-in named templates.
+const TEMPLATES: &[(&str, &str)] = &[
+    (
+        "plain",
+        "{name} ({size}, expires in {expiry}, download limit {downloads})\n{url}",
+    ),
+    (
+        "markdown",
+        "[{name}]({url}) ({size}, expires in {expiry}, download limit {downloads})",
+    ),
+    (
+        "slack",
+        "<{url}|{name}> ({size}, expires in {expiry}, download limit {downloads})",
+    ),
+];
+
+lazy_static! {
+    /// The pattern to match template placeholders with.
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{([a-z]+)\}").unwrap();
+}
+
+/// Properties of a shared file, to render messages with.
+#[derive(Debug, Clone)]
+pub struct ShareInfo {
+    /// The share URL.
+    pub url: Url,
+
+    /// The file name.
+    pub name: Option<String>,
+
+    /// The file size in bytes.
+    pub size: Option<u64>,
+
+    /// The time after which the file expires.
+    pub expiry: Option<Duration>,
+
+    /// The download limit.
+    pub downloads: Option<usize>,
+
+    /// The file password.
+    pub password: Option<String>,
+
+    /// The owner token.
+    pub owner: Option<String>,
+
+    /// The recipient the file was shared for.
+    pub recipient: Option<String>,
+}
+
+impl ShareInfo {
+    /// Render the given template with the properties of this file.
+    ///
+    /// Placeholders for unknown properties are replaced with an empty string.
+    pub fn render(&self, template: &str) -> String {
+        let template = template.replace("\\n", "\n").replace("\\t", "\t");
+        PLACEHOLDER
+            .replace_all(&template, |captures: &Captures| {
+                self.value(&captures[1])
+                    .map(|value| value.unwrap_or_default())
+                    .unwrap_or_else(|| captures[0].to_owned())
+            })
+            .into_owned()
+    }
+
+    /// Get the value for the placeholder with the given name.
+    ///
+    /// Returns `None` if the placeholder is unknown, and `Some(None)` if the property is unknown.
+    fn value(&self, placeholder: &str) -> Option<Option<String>> {
+        Some(match placeholder {
+            "url" => Some(self.url.to_string()),
+            "name" => self.name.clone(),
+            "size" => self.size.map(format_bytes),
+            "expiry" => self.expiry.map(format_duration),
+            "downloads" => self.downloads.map(|d| d.to_string()),
+            "password" => self.password.clone(),
+            "owner" => self.owner.clone(),
+            "recipient" => self.recipient.clone(),
+            _ => return None,
+        })
+    }
+}
+
+/// What to copy of a shared file.
+#[cfg(feature = "clipboard")]
+#[derive(Debug, Clone)]
+pub enum CopyMode {
+    /// Copy the public share link.
+    Url,
+
+    /// Copy an ffsend download command.
+    DownloadCmd,
+
+    /// Copy the share link and password.
+    UrlPassword,
+
+    /// Copy the password.
+    Password,
+
+    /// Copy the owner token.
+    Owner,
+
+    /// Copy a share message rendered from the given template.
+    Message(String),
+}
+
+#[cfg(feature = "clipboard")]
+impl CopyMode {
+    /// Get a list of all copy mode names.
+    pub fn names() -> &'static [&'static str] {
+        &["url", "cmd", "url-password", "password", "owner", "message"]
+    }
+
+    /// Select a copy mode by the given name, case insensitive.
+    ///
+    /// The `template` is used for the message mode, the `plain` template if not set.
+    pub fn from_name(name: &str, template: Option<String>) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "url" | "link" => Some(CopyMode::Url),
+            "cmd" | "command" => Some(CopyMode::DownloadCmd),
+            "url-password" => Some(CopyMode::UrlPassword),
+            "password" => Some(CopyMode::Password),
+            "owner" | "owner-token" => Some(CopyMode::Owner),
+            "message" => Some(CopyMode::Message(
+                template.unwrap_or_else(|| self::template("plain")),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Build the string to copy for the given shared file.
+    ///
+    /// The `url` to copy may differ from the share URL, such as when shortened. The secret
+    /// `key` must be given if it isn't part of the URL.
+    /// `None` is returned if the property to copy is unknown.
+    pub fn build(&self, info: &ShareInfo, url: &str, key: Option<&str>) -> Option<String> {
+        match (self, key) {
+            (CopyMode::Url, _) => Some(url.into()),
+            (CopyMode::DownloadCmd, None) => Some(format!("{} download {}", bin_name(), url)),
+            (CopyMode::DownloadCmd, Some(key)) => {
+                Some(format!("{} download {} --key {}", bin_name(), url, key))
+            }
+            (CopyMode::UrlPassword, _) => Some(match &info.password {
+                Some(password) => format!("{}\n{}", url, password),
+                None => url.into(),
+            }),
+            (CopyMode::Password, _) => info.password.clone(),
+            (CopyMode::Owner, _) => info.owner.clone(),
+            (CopyMode::Message(template), _) => Some(info.render(template)),
+        }
+    }
+
+    /// Describe what this mode copies, to show to the user.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            CopyMode::Url => "share link",
+            CopyMode::DownloadCmd => "download command",
+            CopyMode::UrlPassword => "share link and password",
+            CopyMode::Password => "password",
+            CopyMode::Owner => "owner token",
+            CopyMode::Message(_) => "share message",
+        }
+    }
+}
+
+/// Get a message template, by the given template name or literal template.
+///
+/// Templates configured in the messages file are used first, then the built-in templates.
+/// If no template has the given name, it is used as literal template.
+pub fn template(name: &str) -> String {
+    if let Some(template) = TemplatesConfig::load().and_then(|mut c| c.templates.remove(name)) {
+        return template;
+    }
+    TEMPLATES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, template)| (*template).into())
+        .unwrap_or_else(|| name.into())
+}
+
+/// The messages file.
+#[derive(Debug, Default, Deserialize)]
+struct TemplatesConfig {
+    /// Named templates.
+    #[serde(default)]
+    templates: HashMap<String, String>,
+}
+
+impl TemplatesConfig {
+    /// The path of the messages file.
+    fn path() -> PathBuf {
+        app_project_dirs().config_dir().join("messages.toml")
+    }
+
+    /// Load the messages file, if it exists.
+    ///
+    /// If the file is invalid, the program quits with an error message.
+    fn load() -> Option<Self> {
+        let path = Self::path();
+        let data = fs::read_to_string(&path).ok()?;
+        match toml::from_str(&data) {
+            Ok(config) => Some(config),
+            Err(err) => quit_error_msg(
+                format!(
+                    "invalid messages file at '{}': {}",
+                    path.to_str().unwrap_or("?"),
+                    err,
+                ),
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            ),
+        }
+    }
+}
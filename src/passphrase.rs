@@ -0,0 +1,233 @@
+//! Passphrase generation, based on a configurable policy.
+//!
+//! Passphrases are either built from random words using diceware, or from random characters of a
+//! given character set. The entropy of generated passphrases can be estimated from the policy.
+
+use std::path::PathBuf;
+
+use chbs::{
+    config::BasicConfig,
+    prelude::*,
+    probability::Probability,
+    word::{WordList, WordListError, WordSampler},
+};
+use failure::Fail;
+use rand::{thread_rng, Rng};
+
+/// The default number of words in a passphrase.
+pub const WORDS_DEFAULT: usize = 5;
+
+/// The default separator between passphrase words.
+pub const SEPARATOR_DEFAULT: &str = " ";
+
+/// The default length of random character passwords.
+pub const LENGTH_DEFAULT: usize = 24;
+
+/// Symbols included in the `symbols` character set.
+const SYMBOLS: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+/// A policy to generate passphrases with.
+#[derive(Debug, Clone)]
+pub enum Policy {
+    /// A passphrase of random words.
+    Words {
+        /// The number of words.
+        words: usize,
+
+        /// The separator between words.
+        separator: String,
+
+        /// The capitalization of words.
+        case: Case,
+
+        /// A custom wordlist file, the built-in wordlist is used if not set.
+        wordlist: Option<PathBuf>,
+    },
+
+    /// A password of random characters.
+    Chars {
+        /// The number of characters.
+        length: usize,
+
+        /// The character set to pick characters from.
+        charset: Charset,
+    },
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy::Words {
+            words: WORDS_DEFAULT,
+            separator: SEPARATOR_DEFAULT.into(),
+            case: Case::Mixed,
+            wordlist: None,
+        }
+    }
+}
+
+impl Policy {
+    /// Generate a passphrase based on this policy.
+    pub fn generate(&self) -> Result<String, Error> {
+        match self {
+            Policy::Words {
+                words,
+                separator,
+                case,
+                wordlist,
+            } => Ok(word_config(*words, separator, *case, wordlist.as_ref())?
+                .to_scheme()
+                .generate()),
+            Policy::Chars { length, charset } => {
+                let chars = charset.chars();
+                let mut rng = thread_rng();
+                Ok((0..*length)
+                    .map(|_| chars[rng.gen_range(0..chars.len())])
+                    .collect())
+            }
+        }
+    }
+
+    /// Estimate the entropy of passphrases generated by this policy, in bits.
+    pub fn entropy(&self) -> Result<f64, Error> {
+        match self {
+            Policy::Words {
+                words,
+                separator,
+                case,
+                wordlist,
+            } => Ok(word_config(*words, separator, *case, wordlist.as_ref())?
+                .to_scheme()
+                .entropy()
+                .bits()),
+            Policy::Chars { length, charset } => {
+                Ok(*length as f64 * (charset.chars().len() as f64).log2())
+            }
+        }
+    }
+}
+
+/// Format the given entropy in bits as estimate to show to the user.
+pub fn format_entropy(bits: f64) -> String {
+    format!("~{:.0} bits", bits.floor())
+}
+
+/// Build the passphrase configuration for a word based policy.
+fn word_config(
+    words: usize,
+    separator: &str,
+    case: Case,
+    wordlist: Option<&PathBuf>,
+) -> Result<BasicConfig<WordSampler>, Error> {
+    let wordlist = match wordlist {
+        Some(path) => WordList::load(path).map_err(Error::Wordlist)?,
+        None => WordList::default(),
+    };
+    let (capitalize_first, capitalize_words) = case.probabilities();
+    Ok(BasicConfig {
+        words,
+        word_provider: wordlist.sampler(),
+        separator: separator.into(),
+        capitalize_first,
+        capitalize_words,
+    })
+}
+
+/// The capitalization of passphrase words.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Case {
+    /// All lowercase words.
+    Lower,
+
+    /// Words with a capital first letter.
+    Title,
+
+    /// All uppercase words.
+    Upper,
+
+    /// Randomly capitalize the first letter of words.
+    Mixed,
+}
+
+impl Case {
+    /// Get a list of all case names.
+    pub fn names() -> &'static [&'static str] {
+        &["lower", "title", "upper", "mixed"]
+    }
+
+    /// Select a case by the given name, case insensitive.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "lower" => Some(Case::Lower),
+            "title" => Some(Case::Title),
+            "upper" => Some(Case::Upper),
+            "mixed" => Some(Case::Mixed),
+            _ => None,
+        }
+    }
+
+    /// The probabilities to capitalize the first letter and whole words with.
+    fn probabilities(self) -> (Probability, Probability) {
+        match self {
+            Case::Lower => (Probability::Never, Probability::Never),
+            Case::Title => (Probability::Always, Probability::Never),
+            Case::Upper => (Probability::Never, Probability::Always),
+            Case::Mixed => (Probability::half(), Probability::Never),
+        }
+    }
+}
+
+/// A character set to generate random character passwords from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Charset(Vec<char>);
+
+impl Charset {
+    /// Parse a character set.
+    ///
+    /// This is either a named set such as `alphanumeric`, or a literal set of characters to use.
+    /// At least two distinct characters are required.
+    pub fn parse(value: &str) -> Option<Self> {
+        let lower: String = ('a'..='z').collect();
+        let upper: String = ('A'..='Z').collect();
+        let digits: String = ('0'..='9').collect();
+        let chars: String = match value.trim().to_lowercase().as_str() {
+            "alphanumeric" | "alnum" => format!("{}{}{}", lower, upper, digits),
+            "alphabetic" | "alpha" => format!("{}{}", lower, upper),
+            "numeric" | "digits" => digits,
+            "hex" => format!("{}abcdef", digits),
+            "symbols" => format!("{}{}{}{}", lower, upper, digits, SYMBOLS),
+            _ => value.into(),
+        };
+
+        // Deduplicate characters, require at least two
+        let mut set: Vec<char> = Vec::new();
+        for c in chars.chars() {
+            if !set.contains(&c) {
+                set.push(c);
+            }
+        }
+        if set.len() < 2 {
+            return None;
+        }
+
+        Some(Charset(set))
+    }
+
+    /// The characters in this set.
+    pub fn chars(&self) -> &[char] {
+        &self.0
+    }
+}
+
+impl Default for Charset {
+    fn default() -> Self {
+        Charset::parse("alphanumeric").unwrap()
+    }
+}
+
+/// A passphrase generation error.
+#[derive(Debug, Fail)]
+pub enum Error {
+    /// Failed to load the custom wordlist.
+    #[fail(display = "failed to load passphrase wordlist")]
+    Wordlist(#[cause] WordListError),
+}
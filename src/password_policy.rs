@@ -0,0 +1,309 @@
+//! Strength policies for user chosen passwords.
+//!
+//! Passwords chosen by the user are checked against a policy when uploading or changing the
+//! password of a file. The policy is configured in the `password-policy.toml` file in the
+//! configuration directory, with defaults and overrides for specific hosts:
+//!
+//! ```toml
+//! [default]
+//! mode = "warn"
+//! min_length = 8
+//! min_strength = 2
+//!
+//! [hosts."https://send.example.com/"]
+//! mode = "enforce"
+//! min_length = 12
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use ffsend_api::url::Url;
+use zxcvbn::{feedback::Warning, zxcvbn};
+
+use crate::cmd::matcher::MainMatcher;
+use crate::util::{
+    app_project_dirs, highlight, print_error_msg, print_warning, quit_error_msg, ErrorHintsBuilder,
+};
+
+/// The default minimum password length.
+const MIN_LENGTH_DEFAULT: usize = 8;
+
+/// The default minimum password strength score, from 0 to 4.
+const MIN_STRENGTH_DEFAULT: u8 = 2;
+
+/// How to apply a password policy.
+///
+/// Modes are ordered from least to most strict.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Don't check passwords.
+    Off,
+
+    /// Warn about passwords violating the policy.
+    Warn,
+
+    /// Refuse passwords violating the policy.
+    Enforce,
+}
+
+impl Mode {
+    /// Get a list of all mode names.
+    pub fn names() -> &'static [&'static str] {
+        &["off", "warn", "enforce"]
+    }
+
+    /// Get the name of this mode.
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Off => "off",
+            Mode::Warn => "warn",
+            Mode::Enforce => "enforce",
+        }
+    }
+
+    /// Select a mode by the given name, case insensitive.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "off" => Some(Mode::Off),
+            "warn" => Some(Mode::Warn),
+            "enforce" => Some(Mode::Enforce),
+            _ => None,
+        }
+    }
+}
+
+/// A password policy.
+#[derive(Debug, Clone)]
+pub struct Policy {
+    /// How to apply the policy.
+    pub mode: Mode,
+
+    /// The minimum password length in characters.
+    pub min_length: usize,
+
+    /// The minimum strength score of the password, from 0 to 4.
+    pub min_strength: u8,
+
+    /// Whether to reject commonly used passwords.
+    pub reject_common: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Warn,
+            min_length: MIN_LENGTH_DEFAULT,
+            min_strength: MIN_STRENGTH_DEFAULT,
+            reject_common: true,
+        }
+    }
+}
+
+impl Policy {
+    /// Get the password policy for the given host.
+    ///
+    /// The policy is loaded from the policy file, host specific settings override the defaults.
+    /// The given `mode` overrides the mode if set, but may only make a mode configured in the
+    /// policy file stricter. A less strict mode is ignored with a warning.
+    pub fn for_host(host: &Url, mode: Option<Mode>) -> Self {
+        let mut policy = Self::default();
+        let mut configured = None;
+        if let Some(config) = PolicyConfig::load() {
+            policy.apply(&config.default);
+            configured = config.default.mode;
+            if let Some(host_config) = config.hosts.get(host.as_str()) {
+                policy.apply(host_config);
+                configured = host_config.mode.or(configured);
+            }
+        }
+        match (mode, configured) {
+            (Some(mode), Some(configured)) if mode < configured => print_warning(format!(
+                "ignoring password policy mode '{}', the policy file configures the stricter \
+                 mode '{}'",
+                mode.name(),
+                configured.name(),
+            )),
+            (Some(mode), _) => policy.mode = mode,
+            (None, _) => {}
+        }
+        policy
+    }
+
+    /// Summarize this policy to show to the user.
+    pub fn summary(&self) -> String {
+        if self.mode == Mode::Off {
+            return self.mode.name().into();
+        }
+        format!(
+            "{}, at least {} characters, strength {} of 4{}",
+            self.mode.name(),
+            self.min_length,
+            self.min_strength,
+            if self.reject_common {
+                ", no common passwords"
+            } else {
+                ""
+            },
+        )
+    }
+
+    /// Apply the given configured settings to this policy.
+    fn apply(&mut self, config: &PolicySettings) {
+        if let Some(mode) = config.mode {
+            self.mode = mode;
+        }
+        if let Some(min_length) = config.min_length {
+            self.min_length = min_length;
+        }
+        if let Some(min_strength) = config.min_strength {
+            self.min_strength = min_strength.min(4);
+        }
+        if let Some(reject_common) = config.reject_common {
+            self.reject_common = reject_common;
+        }
+    }
+
+    /// Check the given password against this policy, get a list of violations.
+    ///
+    /// The list is empty if the password is fine.
+    pub fn violations(&self, password: &str) -> Vec<String> {
+        let mut violations = Vec::new();
+        if self.mode == Mode::Off {
+            return violations;
+        }
+
+        // Check the password length
+        let length = password.chars().count();
+        if length < self.min_length {
+            violations.push(format!(
+                "the password is {} characters, at least {} are required",
+                length, self.min_length,
+            ));
+        }
+
+        // Estimate the password strength
+        let estimate = match zxcvbn(password, &[]) {
+            Ok(estimate) => estimate,
+            Err(_) => return violations,
+        };
+        let warning = estimate.feedback().as_ref().and_then(|f| f.warning());
+        let common = matches!(
+            warning,
+            Some(Warning::ThisIsATop10Password)
+                | Some(Warning::ThisIsATop100Password)
+                | Some(Warning::ThisIsACommonPassword)
+        );
+        if self.reject_common && common {
+            violations.push("the password is a commonly used password".into());
+        } else if estimate.score() < self.min_strength {
+            let mut violation = format!(
+                "the password strength is {} of 4, at least {} is required",
+                estimate.score(),
+                self.min_strength,
+            );
+            if let Some(warning) = warning {
+                violation.push_str(&format!(" ({})", warning.to_string().trim_end_matches('.')));
+            }
+            violations.push(violation);
+        }
+
+        violations
+    }
+
+    /// Check the given password against this policy.
+    ///
+    /// Violations are reported as warnings, or the program quits with an error message when the
+    /// policy is enforced.
+    pub fn check(&self, password: &str, matcher_main: &MainMatcher) {
+        let violations = self.violations(password);
+        if violations.is_empty() {
+            return;
+        }
+
+        match self.mode {
+            Mode::Off => {}
+            Mode::Warn => {
+                if !matcher_main.quiet() {
+                    for violation in &violations {
+                        print_warning(format!("weak password, {}", violation));
+                    }
+                }
+            }
+            Mode::Enforce => {
+                let (last, others) = violations.split_last().unwrap();
+                for violation in others {
+                    print_error_msg(violation.clone());
+                }
+                quit_error_msg(
+                    last.clone(),
+                    ErrorHintsBuilder::default()
+                        .add_info(format!(
+                            "Use '{}' to generate a strong passphrase instead",
+                            highlight("--gen-passphrase")
+                        ))
+                        .verbose(false)
+                        .build()
+                        .unwrap(),
+                );
+            }
+        }
+    }
+}
+
+/// The password policy file.
+#[derive(Debug, Default, Deserialize)]
+struct PolicyConfig {
+    /// The default policy settings.
+    #[serde(default)]
+    default: PolicySettings,
+
+    /// Policy settings overriding the defaults, by host URL.
+    #[serde(default)]
+    hosts: HashMap<String, PolicySettings>,
+}
+
+/// Password policy settings in the policy file.
+#[derive(Debug, Default, Deserialize)]
+struct PolicySettings {
+    /// How to apply the policy.
+    mode: Option<Mode>,
+
+    /// The minimum password length in characters.
+    min_length: Option<usize>,
+
+    /// The minimum strength score of the password, from 0 to 4.
+    min_strength: Option<u8>,
+
+    /// Whether to reject commonly used passwords.
+    reject_common: Option<bool>,
+}
+
+impl PolicyConfig {
+    /// The path of the policy file.
+    fn path() -> PathBuf {
+        app_project_dirs().config_dir().join("password-policy.toml")
+    }
+
+    /// Load the policy file, if it exists.
+    ///
+    /// If the file is invalid, the program quits with an error message, to not silently weaken
+    /// a configured policy.
+    fn load() -> Option<Self> {
+        let path = Self::path();
+        let data = fs::read_to_string(&path).ok()?;
+        match toml::from_str(&data) {
+            Ok(config) => Some(config),
+            Err(err) => quit_error_msg(
+                format!(
+                    "invalid password policy file at '{}': {}",
+                    path.to_str().unwrap_or("?"),
+                    err,
+                ),
+                ErrorHintsBuilder::default().verbose(false).build().unwrap(),
+            ),
+        }
+    }
+}
@@ -1,8 +1,14 @@
-use std::io::{stderr, Stderr};
+use std::fs::File;
+use std::io::{self, stderr, Error as IoError, ErrorKind, Read, Stderr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use ffsend_api::pipe::ProgressReporter;
 use pbr::{ProgressBar as Pbr, Units};
+use sha2::{Digest, Sha256};
+
+use crate::util::hex_string;
 
 /// The refresh rate of the progress bar, in milliseconds.
 const PROGRESS_BAR_FPS_MILLIS: u64 = 200;
@@ -71,3 +77,110 @@ impl<'a> ProgressReporter for ProgressBar<'a> {
         }
     }
 }
+
+/// A progress reporter computing the SHA-256 checksum of an uploaded file while it's streamed.
+///
+/// Uploads read the file through a reader internal to the API, so each progress update hashes
+/// the part of the file the upload just read, in step with the stream. A restarted upload, such
+/// as when retrying, starts over. Progress is forwarded to the wrapped reporter, if any.
+pub struct ChecksumReporter {
+    /// The path of the uploaded file.
+    path: PathBuf,
+
+    /// The reporter to forward progress to.
+    inner: Option<Arc<Mutex<dyn ProgressReporter>>>,
+
+    /// The file being hashed, opened when the upload starts.
+    file: Option<File>,
+
+    /// The hasher, and the number of bytes hashed so far.
+    hasher: Sha256,
+    hashed: u64,
+
+    /// The number of bytes to hash, as reported when the upload starts.
+    total: u64,
+
+    /// An error that occurred while hashing.
+    error: Option<IoError>,
+}
+
+impl ChecksumReporter {
+    /// Construct a checksum reporter for the file at `path`, forwarding progress to `inner`.
+    pub fn new(path: &Path, inner: Option<&Arc<Mutex<dyn ProgressReporter>>>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            inner: inner.cloned(),
+            file: None,
+            hasher: Sha256::new(),
+            hashed: 0,
+            total: 0,
+            error: None,
+        }
+    }
+
+    /// Get the checksum of the uploaded file, as lowercase hexadecimal string.
+    ///
+    /// This fails if the file couldn't be read, or if it wasn't uploaded completely.
+    pub fn checksum(&mut self) -> Result<String, IoError> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        if self.file.is_none() || self.hashed != self.total {
+            return Err(IoError::new(
+                ErrorKind::UnexpectedEof,
+                "file wasn't uploaded completely",
+            ));
+        }
+        Ok(hex_string(&self.hasher.clone().finalize()))
+    }
+
+    /// Hash the file up to the given position.
+    fn hash_to(&mut self, position: u64) -> Result<(), IoError> {
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        let len = position.saturating_sub(self.hashed);
+        let hashed = io::copy(&mut file.take(len), &mut self.hasher)?;
+        self.hashed += hashed;
+        Ok(())
+    }
+}
+
+impl ProgressReporter for ChecksumReporter {
+    fn start(&mut self, total: u64) {
+        self.hasher = Sha256::new();
+        self.hashed = 0;
+        self.total = total;
+        self.error = None;
+        match File::open(&self.path) {
+            Ok(file) => self.file = Some(file),
+            Err(err) => {
+                self.file = None;
+                self.error = Some(err);
+            }
+        }
+
+        if let Some(inner) = &self.inner {
+            inner.lock().unwrap().start(total);
+        }
+    }
+
+    fn progress(&mut self, progress: u64) {
+        if self.error.is_none() {
+            if let Err(err) = self.hash_to(progress) {
+                self.error = Some(err);
+            }
+        }
+
+        if let Some(inner) = &self.inner {
+            inner.lock().unwrap().progress(progress);
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Some(inner) = &self.inner {
+            inner.lock().unwrap().finish();
+        }
+    }
+}
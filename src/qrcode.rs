@@ -0,0 +1,228 @@
+//! QR code rendering and scanning mechanics.
+
+use std::fs;
+use std::io::{self, Error as IoError, Write};
+use std::path::{Path, PathBuf};
+
+use image::{GrayImage, ImageError, Luma};
+use qr::{
+    render::{svg, unicode::Dense1x2},
+    types::QrError,
+    EcLevel, QrCode,
+};
+use qr2term::{
+    matrix::Matrix,
+    render::{QrLight, Renderer},
+};
+
+/// Quiet zone size in pixels around QR codes rendered in the terminal.
+///
+/// Should be 4, but using 2 for small terminals.
+const TERMINAL_QUIET_ZONE: usize = 2;
+
+/// Minimum size in pixels of rendered QR code images.
+const IMAGE_MIN_SIZE: u32 = 512;
+
+/// A QR code render format.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    /// Render in the terminal, with colors.
+    Terminal,
+
+    /// Render as plain text, using UTF-8 half block characters.
+    Utf8HalfBlocks,
+
+    /// Render as PNG image.
+    Png,
+
+    /// Render as SVG image.
+    Svg,
+}
+
+impl Format {
+    /// Get a list of format names that can be selected by the user.
+    pub fn names() -> &'static [&'static str] {
+        &["terminal", "utf8-half-blocks", "png", "svg"]
+    }
+
+    /// Get the format for the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "terminal" => Some(Format::Terminal),
+            "utf8-half-blocks" => Some(Format::Utf8HalfBlocks),
+            "png" => Some(Format::Png),
+            "svg" => Some(Format::Svg),
+            _ => None,
+        }
+    }
+
+    /// Guess the format based on the extension of the given output file.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "png" => Some(Format::Png),
+            "svg" => Some(Format::Svg),
+            "txt" => Some(Format::Utf8HalfBlocks),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the error correction level from the given name.
+pub fn ec_level_from_name(name: &str) -> Option<EcLevel> {
+    match name.trim().to_lowercase().as_str() {
+        "l" => Some(EcLevel::L),
+        "m" => Some(EcLevel::M),
+        "q" => Some(EcLevel::Q),
+        "h" => Some(EcLevel::H),
+        _ => None,
+    }
+}
+
+/// QR code rendering options.
+#[derive(Debug, Clone)]
+pub struct QrOptions {
+    /// The render format, guessed from the output file or terminal if not set.
+    pub format: Option<Format>,
+
+    /// The error correction level.
+    pub ec_level: EcLevel,
+
+    /// The file to write the QR code to, stdout is used if not set.
+    pub output: Option<PathBuf>,
+}
+
+impl QrOptions {
+    /// Get the format to render in.
+    pub fn format(&self) -> Format {
+        self.format
+            .or_else(|| self.output.as_deref().and_then(Format::from_path))
+            .unwrap_or(Format::Terminal)
+    }
+}
+
+/// Render the given `data` as QR code with the given options.
+///
+/// The QR code is written to the output file if specified, or printed to stdout otherwise.
+pub fn render(data: &str, options: &QrOptions) -> Result<(), Error> {
+    let code = QrCode::with_error_correction_level(data, options.ec_level)?;
+    let format = options.format();
+
+    // Render the QR code in the selected format
+    let rendered = match format {
+        Format::Terminal => {
+            let mut matrix = Matrix::new(code.to_colors());
+            matrix.surround(TERMINAL_QUIET_ZONE, QrLight);
+            let mut buf = Vec::new();
+            Renderer::default()
+                .render(&matrix, &mut buf)
+                .map_err(Error::Write)?;
+            buf
+        }
+        Format::Utf8HalfBlocks => {
+            let mut text = code.render::<Dense1x2>().build();
+            text.push('\n');
+            text.into_bytes()
+        }
+        Format::Png => {
+            let image = code
+                .render::<Luma<u8>>()
+                .min_dimensions(IMAGE_MIN_SIZE, IMAGE_MIN_SIZE)
+                .build();
+            match &options.output {
+                Some(path) => return image.save(path).map_err(Error::Image),
+                None => return Err(Error::NoOutput),
+            }
+        }
+        Format::Svg => code
+            .render::<svg::Color>()
+            .min_dimensions(IMAGE_MIN_SIZE, IMAGE_MIN_SIZE)
+            .build()
+            .into_bytes(),
+    };
+
+    // Write the result to the output file or stdout
+    match &options.output {
+        Some(path) => fs::write(path, rendered).map_err(Error::Write),
+        None => io::stdout().write_all(&rendered).map_err(Error::Write),
+    }
+}
+
+/// Scan the QR code in the given image file, and return its contents.
+///
+/// If no QR code is found, the image is scanned again with inverted colors to support
+/// light-on-dark codes, such as those captured from a terminal.
+pub fn scan(path: &Path) -> Result<String, ScanError> {
+    let image = image::open(path).map_err(ScanError::Open)?.to_luma8();
+
+    // Scan the image as is, then try with inverted colors
+    match scan_image(&image, false)? {
+        Some(data) => Ok(data),
+        None => scan_image(&image, true)?.ok_or(ScanError::NotFound),
+    }
+}
+
+/// Scan the given greyscale image for QR codes, and decode the first one found.
+fn scan_image(image: &GrayImage, invert: bool) -> Result<Option<String>, ScanError> {
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| {
+            let luma = image.get_pixel(x as u32, y as u32).0[0];
+            if invert {
+                255 - luma
+            } else {
+                luma
+            }
+        },
+    );
+
+    match prepared.detect_grids().first() {
+        Some(grid) => grid
+            .decode()
+            .map(|(_, data)| Some(data))
+            .map_err(ScanError::Decode),
+        None => Ok(None),
+    }
+}
+
+/// A QR code rendering error.
+#[derive(Debug, Fail)]
+pub enum Error {
+    /// Failed to generate the QR code, the data may be too long.
+    #[fail(display = "failed to generate QR code")]
+    Generate(#[cause] QrError),
+
+    /// An image format was selected that can't be printed, but no output file was given.
+    #[fail(display = "no output file given for the QR code image, use --qrcode-output")]
+    NoOutput,
+
+    /// Failed to write the rendered QR code.
+    #[fail(display = "failed to write QR code")]
+    Write(#[cause] IoError),
+
+    /// Failed to encode or write the QR code image.
+    #[fail(display = "failed to write QR code image")]
+    Image(#[cause] ImageError),
+}
+
+impl From<QrError> for Error {
+    fn from(err: QrError) -> Self {
+        Error::Generate(err)
+    }
+}
+
+/// A QR code scanning error.
+#[derive(Debug, Fail)]
+pub enum ScanError {
+    /// Failed to open or read the image file.
+    #[fail(display = "failed to open QR code image")]
+    Open(#[cause] ImageError),
+
+    /// No QR code was found in the image.
+    #[fail(display = "no QR code found in the image")]
+    NotFound,
+
+    /// A QR code was found, but it could not be decoded.
+    #[fail(display = "failed to decode QR code in the image")]
+    Decode(#[cause] rqrr::DeQRError),
+}
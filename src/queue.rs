@@ -0,0 +1,280 @@
+//! A local queue of uploads to run later.
+//!
+//! Jobs are kept in the data directory along with the upload options they were queued with, and
+//! are run as separate `upload` invocations by `queue run`. Results of runs are appended to a
+//! log file next to the queue.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Error as IoError, Write};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use failure::Fail;
+use fs2::{lock_contended_error, FileExt};
+use tempfile::NamedTempFile;
+use toml::{de::Error as DeError, ser::Error as SerError};
+
+use crate::util::app_project_dirs;
+
+/// A queue of uploads.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Queue {
+    /// The ID to give the next job.
+    #[serde(default)]
+    next_id: u32,
+
+    /// The queued jobs, in order.
+    #[serde(default)]
+    jobs: Vec<Job>,
+}
+
+/// A queued upload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    /// The job ID.
+    pub id: u32,
+
+    /// The files to upload.
+    pub files: Vec<PathBuf>,
+
+    /// The upload options, as given on the command line.
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// The time to upload at, as soon as possible if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<DateTime<Utc>>,
+
+    /// The time the job was queued at.
+    pub added: DateTime<Utc>,
+
+    /// The number of failed upload attempts.
+    #[serde(default)]
+    pub attempts: u32,
+
+    /// The error of the last failed attempt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Job {
+    /// Check whether the job is due to run at the given time.
+    pub fn due(&self, now: DateTime<Utc>) -> bool {
+        self.at.map(|at| at <= now).unwrap_or(true)
+    }
+}
+
+impl Queue {
+    /// The path of the queue file.
+    pub fn path() -> PathBuf {
+        app_project_dirs().data_dir().join("queue.toml")
+    }
+
+    /// The path of the results log file.
+    pub fn log_path() -> PathBuf {
+        app_project_dirs().data_dir().join("queue.log")
+    }
+
+    /// The path of the lock file, locked while the queue is run.
+    fn lock_path() -> PathBuf {
+        app_project_dirs().data_dir().join("queue.lock")
+    }
+
+    /// The path of the lock file, locked while the queue file is changed.
+    fn update_lock_path() -> PathBuf {
+        app_project_dirs().data_dir().join("queue.update.lock")
+    }
+
+    /// Load the queue, an empty queue is returned if there is no queue file.
+    pub fn load() -> Result<Self, Error> {
+        let path = Self::path();
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(&path).map_err(Error::Read)?;
+        toml::from_str(&data).map_err(Error::Parse)
+    }
+
+    /// Save the queue, the queue file is removed if there are no jobs.
+    ///
+    /// The queue is written to a temporary file first, which then replaces the queue file, so the
+    /// queue file is never left partially written. Use `update` to change the queue.
+    fn save(&self) -> Result<(), Error> {
+        let path = Self::path();
+        if self.jobs.is_empty() {
+            if path.is_file() {
+                fs::remove_file(&path).map_err(Error::Write)?;
+            }
+            return Ok(());
+        }
+
+        // Ensure the parent directory exists
+        let parent = path.parent().expect("queue file has no parent directory");
+        fs::create_dir_all(parent).map_err(Error::Write)?;
+
+        // Upload options may hold passwords, temporary files are only readable by the user
+        let data = toml::to_string(self).map_err(Error::Serialize)?;
+        let mut file = NamedTempFile::new_in(parent).map_err(Error::Write)?;
+        file.write_all(data.as_bytes()).map_err(Error::Write)?;
+        file.as_file().sync_all().map_err(Error::Write)?;
+        file.persist(&path)
+            .map(drop)
+            .map_err(|err| Error::Write(err.error))
+    }
+
+    /// Load the queue, change it with the given function, and save it.
+    ///
+    /// The queue is locked while doing so, separate from the lock for running the queue, so
+    /// concurrent changes such as adding a job while the queue is run aren't lost.
+    pub fn update<F, T>(change: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        let _lock = Lock::acquire(Self::update_lock_path(), true)?;
+        let mut queue = Self::load()?;
+        let result = change(&mut queue);
+        queue.save()?;
+        Ok(result)
+    }
+
+    /// Add a job to the queue, returns its ID.
+    pub fn add(
+        &mut self,
+        files: Vec<PathBuf>,
+        args: Vec<String>,
+        at: Option<DateTime<Utc>>,
+    ) -> u32 {
+        self.next_id += 1;
+        self.jobs.push(Job {
+            id: self.next_id,
+            files,
+            args,
+            at,
+            added: Utc::now(),
+            attempts: 0,
+            error: None,
+        });
+        self.next_id
+    }
+
+    /// Get the queued jobs, in order.
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    /// Get the job with the given ID.
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut Job> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    /// Remove the job with the given ID, returns whether any job was removed.
+    pub fn remove(&mut self, id: u32) -> bool {
+        let len = self.jobs.len();
+        self.jobs.retain(|job| job.id != id);
+        self.jobs.len() != len
+    }
+
+    /// Lock the queue for running, so it isn't run twice at the same time.
+    ///
+    /// This takes an advisory lock on the lock file, which the system releases when the returned
+    /// guard is dropped or this process exits.
+    pub fn lock() -> Result<Lock, Error> {
+        Lock::acquire(Self::lock_path(), false)
+    }
+
+    /// Append a line for a job result to the results log.
+    pub fn log(job: &Job, status: &str, detail: &str) -> Result<(), Error> {
+        let path = Self::log_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(Error::Log)?;
+        }
+        let files: Vec<_> = job.files.iter().map(|f| f.to_string_lossy()).collect();
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(Error::Log)?;
+        writeln!(
+            log,
+            "{}\t{}\t{}\t{}\t{}",
+            Utc::now().to_rfc3339(),
+            job.id,
+            status,
+            files.join(" "),
+            detail,
+        )
+        .map_err(Error::Log)
+    }
+}
+
+/// A lock on the queue, released when dropped.
+pub struct Lock {
+    /// The locked lock file.
+    file: File,
+}
+
+impl Lock {
+    /// Take an advisory lock on the lock file at the given path.
+    ///
+    /// If `wait` is set, this blocks until the lock is available. Otherwise `Error::Locked` is
+    /// returned if the file is already locked.
+    fn acquire(path: PathBuf, wait: bool) -> Result<Self, Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(Error::Lock)?;
+        }
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(Error::Lock)?;
+        let result = if wait {
+            file.lock_exclusive()
+        } else {
+            file.try_lock_exclusive()
+        };
+        match result {
+            Ok(()) => Ok(Self { file }),
+            Err(err) if err.kind() == lock_contended_error().kind() => Err(Error::Locked),
+            Err(err) => Err(Error::Lock(err)),
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// A queue error.
+#[derive(Debug, Fail)]
+pub enum Error {
+    /// Failed to read the queue file.
+    #[fail(display = "failed to read the upload queue file")]
+    Read(#[cause] IoError),
+
+    /// Failed to parse the queue file.
+    #[fail(display = "failed to parse the upload queue file")]
+    Parse(#[cause] DeError),
+
+    /// Failed to serialize the queue for saving.
+    #[fail(display = "failed to serialize the upload queue for saving")]
+    Serialize(#[cause] SerError),
+
+    /// Failed to write the queue file.
+    #[fail(display = "failed to write the upload queue file")]
+    Write(#[cause] IoError),
+
+    /// The queue is already being run.
+    #[fail(display = "the upload queue is already being run")]
+    Locked,
+
+    /// Failed to lock the queue.
+    #[fail(display = "failed to lock the upload queue")]
+    Lock(#[cause] IoError),
+
+    /// Failed to write to the results log.
+    #[fail(display = "failed to write to the upload queue results log")]
+    Log(#[cause] IoError),
+}
@@ -0,0 +1,316 @@
+use std::error::Error as StdError;
+use std::thread;
+use std::time::Duration;
+
+use chrono::Duration as ChronoDuration;
+use ffsend_api::action::download::{DownloadError, Error as DownloadActionError};
+use ffsend_api::action::exists::Error as ExistsError;
+use ffsend_api::action::info::{Error as InfoActionError, InfoError, PrepareError};
+use ffsend_api::action::metadata::{Error as MetadataError, MetaError, RequestError};
+use ffsend_api::action::upload::{Error as UploadActionError, UploadError};
+use ffsend_api::action::version::Error as VersionError;
+use ffsend_api::api::nonce::NonceError;
+use ffsend_api::api::request::ResponseError;
+use ffsend_api::reqwest::StatusCode;
+
+use crate::cmd::matcher::MainMatcher;
+use crate::util::{format_duration, print_warning};
+
+/// A policy for retrying ffsend API actions that failed because of a transient network error.
+///
+/// The time to wait between attempts is doubled after each retry.
+#[derive(Debug, Clone, Copy)]
+pub struct Retry {
+    /// The number of times to retry an action.
+    retries: u32,
+
+    /// The time to wait before the first retry.
+    backoff: Duration,
+
+    /// Whether to report retries.
+    verbose: bool,
+}
+
+impl Retry {
+    /// Create the retry policy for ffsend API actions, as configured by the user.
+    pub fn new(matcher_main: &MainMatcher) -> Self {
+        Self {
+            retries: matcher_main.retries(),
+            backoff: Duration::from_secs(matcher_main.retry_backoff()),
+            verbose: matcher_main.verbose(),
+        }
+    }
+
+    /// Invoke the given action, retry it if it fails with a transient error.
+    ///
+    /// The `what` describes the action in retry reports, such as `upload`.
+    /// Permanent errors, and the error of the last attempt, are returned.
+    pub fn invoke<T, E, F>(&self, what: &str, mut action: F) -> Result<T, E>
+    where
+        E: Transient + StdError,
+        F: FnMut() -> Result<T, E>,
+    {
+        let mut backoff = self.backoff;
+        let mut retry = 0;
+        loop {
+            match action() {
+                Err(ref err) if retry < self.retries && err.is_transient() => {
+                    retry += 1;
+                    if self.verbose {
+                        let wait = match ChronoDuration::from_std(backoff) {
+                            Ok(wait) if backoff.as_secs() > 0 => {
+                                format!(" in {}", format_duration(wait))
+                            }
+                            _ => String::new(),
+                        };
+                        print_warning(format!(
+                            "{} failed: {}, retrying{} ({}/{})",
+                            what,
+                            describe(err),
+                            wait,
+                            retry,
+                            self.retries,
+                        ));
+                    }
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Describe the given error along with its causes on a single line.
+fn describe(err: &dyn StdError) -> String {
+    let mut parts = vec![err.to_string()];
+    let mut source = err.source();
+    while let Some(err) = source {
+        parts.push(err.to_string());
+        source = err.source();
+    }
+    parts.retain(|part| !part.is_empty());
+    parts.join(": ")
+}
+
+/// An error that may be transient, such as a failed request because of a network issue.
+///
+/// Actions failing with a transient error may succeed when retried, while retrying an action
+/// failing with a permanent error, such as an expired file or a wrong password, is pointless.
+pub trait Transient {
+    /// Check whether this error is transient.
+    fn is_transient(&self) -> bool;
+}
+
+impl Transient for ResponseError {
+    fn is_transient(&self) -> bool {
+        match self {
+            ResponseError::OtherHttp(status, _) => {
+                status.is_server_error()
+                    || *status == StatusCode::REQUEST_TIMEOUT
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            ResponseError::Expired | ResponseError::Unauthorized | ResponseError::Undefined => {
+                false
+            }
+        }
+    }
+}
+
+impl Transient for NonceError {
+    fn is_transient(&self) -> bool {
+        match self {
+            NonceError::Request => true,
+            NonceError::Response(err) => err.is_transient(),
+            _ => false,
+        }
+    }
+}
+
+impl Transient for VersionError {
+    fn is_transient(&self) -> bool {
+        match self {
+            VersionError::Request => true,
+            VersionError::Unknown | VersionError::Unsupported(_) => false,
+        }
+    }
+}
+
+impl Transient for ExistsError {
+    fn is_transient(&self) -> bool {
+        match self {
+            ExistsError::Request => true,
+            ExistsError::Response(err) => err.is_transient(),
+            ExistsError::Malformed => false,
+        }
+    }
+}
+
+impl Transient for InfoActionError {
+    fn is_transient(&self) -> bool {
+        match self {
+            InfoActionError::Prepare(PrepareError::Auth(err)) => err.is_transient(),
+            InfoActionError::Info(InfoError::Request) => true,
+            InfoActionError::Info(InfoError::Response(err)) => err.is_transient(),
+            _ => false,
+        }
+    }
+}
+
+impl Transient for MetadataError {
+    fn is_transient(&self) -> bool {
+        match self {
+            MetadataError::Exists(err) => err.is_transient(),
+            MetadataError::Request(RequestError::Auth(err)) => err.is_transient(),
+            MetadataError::Request(RequestError::Meta(err)) => match err {
+                MetaError::NonceRequest => true,
+                MetaError::NonceResponse(err) => err.is_transient(),
+                MetaError::Nonce(err) => err.is_transient(),
+                _ => false,
+            },
+            MetadataError::Expired | MetadataError::PasswordRequired => false,
+        }
+    }
+}
+
+impl Transient for DownloadActionError {
+    fn is_transient(&self) -> bool {
+        match self {
+            DownloadActionError::Meta(err) => err.is_transient(),
+            // The transfer itself may be dropped halfway
+            DownloadActionError::Download(DownloadError::Request)
+            | DownloadActionError::Download(DownloadError::Download) => true,
+            DownloadActionError::Download(DownloadError::Response(err)) => err.is_transient(),
+            _ => false,
+        }
+    }
+}
+
+impl Transient for UploadActionError {
+    fn is_transient(&self) -> bool {
+        // Only retry failures before the upload stream completes, the server may already have
+        // stored the file afterwards, such as when the final status response is invalid, and
+        // retrying would upload it again. Changing parameters or the password happens after
+        // uploading as well.
+        match self {
+            UploadActionError::Upload(UploadError::Request) => true,
+            #[cfg(feature = "send3")]
+            UploadActionError::Upload(UploadError::UploadStream(_)) => true,
+            UploadActionError::Upload(UploadError::Response(err)) => err.is_transient(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http(status: StatusCode) -> ResponseError {
+        ResponseError::OtherHttp(status, String::new())
+    }
+
+    #[test]
+    fn response_transient() {
+        assert!(http(StatusCode::SERVICE_UNAVAILABLE).is_transient());
+        assert!(http(StatusCode::INTERNAL_SERVER_ERROR).is_transient());
+        assert!(http(StatusCode::REQUEST_TIMEOUT).is_transient());
+        assert!(http(StatusCode::TOO_MANY_REQUESTS).is_transient());
+    }
+
+    #[test]
+    fn response_permanent() {
+        assert!(!http(StatusCode::NOT_FOUND).is_transient());
+        assert!(!http(StatusCode::BAD_REQUEST).is_transient());
+        assert!(!ResponseError::Expired.is_transient());
+        assert!(!ResponseError::Unauthorized.is_transient());
+        assert!(!ResponseError::Undefined.is_transient());
+    }
+
+    #[test]
+    fn exists_transient() {
+        assert!(ExistsError::Request.is_transient());
+        assert!(ExistsError::Response(http(StatusCode::BAD_GATEWAY)).is_transient());
+        assert!(!ExistsError::Response(ResponseError::Expired).is_transient());
+        assert!(!ExistsError::Malformed.is_transient());
+    }
+
+    #[test]
+    fn version_transient() {
+        assert!(VersionError::Request.is_transient());
+        assert!(!VersionError::Unknown.is_transient());
+    }
+
+    #[test]
+    fn metadata_transient() {
+        assert!(MetadataError::Exists(ExistsError::Request).is_transient());
+        assert!(!MetadataError::Expired.is_transient());
+        assert!(!MetadataError::PasswordRequired.is_transient());
+    }
+
+    #[test]
+    fn download_transient() {
+        assert!(DownloadActionError::Download(DownloadError::Request).is_transient());
+        assert!(DownloadActionError::Download(DownloadError::Download).is_transient());
+        assert!(DownloadActionError::Download(DownloadError::Response(http(
+            StatusCode::SERVICE_UNAVAILABLE
+        )))
+        .is_transient());
+        assert!(
+            !DownloadActionError::Download(DownloadError::Response(ResponseError::Expired))
+                .is_transient()
+        );
+    }
+
+    #[test]
+    fn upload_transient() {
+        assert!(UploadActionError::Upload(UploadError::Request).is_transient());
+        assert!(
+            UploadActionError::Upload(UploadError::Response(http(StatusCode::BAD_GATEWAY)))
+                .is_transient()
+        );
+    }
+
+    #[test]
+    fn upload_permanent() {
+        // May be returned after the upload stream completed
+        assert!(!UploadActionError::Upload(UploadError::InvalidResponse).is_transient());
+        assert!(
+            !UploadActionError::Upload(UploadError::Response(ResponseError::Undefined))
+                .is_transient()
+        );
+        assert!(!UploadActionError::Upload(UploadError::Progress).is_transient());
+    }
+
+    #[test]
+    fn retry_transient() {
+        let retry = Retry {
+            retries: 2,
+            backoff: Duration::from_secs(0),
+            verbose: false,
+        };
+        let mut attempts = 0;
+        let result: Result<(), _> = retry.invoke("test", || {
+            attempts += 1;
+            Err(ExistsError::Request)
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn retry_permanent() {
+        let retry = Retry {
+            retries: 2,
+            backoff: Duration::from_secs(0),
+            verbose: false,
+        };
+        let mut attempts = 0;
+        let result: Result<(), _> = retry.invoke("test", || {
+            attempts += 1;
+            Err(ExistsError::Malformed)
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
@@ -0,0 +1,436 @@
+//! Storage for owner tokens outside of the history file.
+//!
+//! Owner tokens give full control over uploaded files. Instead of keeping them in the plain
+//! history file, they may be stored in the system keyring through the Secret Service, or in an
+//! encrypted file on headless systems without a keyring. The history file then only holds
+//! non-secret metadata.
+//!
+//! Tokens are keyed by the share URL of a file, without its secret.
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io::Error as IoError;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+
+use failure::Fail;
+use ffsend_api::file::remote_file::RemoteFile;
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use rpassword::prompt_password_stderr;
+
+use crate::cmd::matcher::MainMatcher;
+use crate::util::app_project_dirs;
+
+/// The keyring service name owner tokens are stored under.
+const KEYRING_SERVICE: &str = "ffsend";
+
+/// The version of the encrypted secret store file format.
+const FILE_VERSION: u32 = 1;
+
+/// The number of PBKDF2 iterations to derive the secret store file key with.
+const FILE_KDF_ITERATIONS: u32 = 100_000;
+
+/// The length of the secret store file key salt in bytes.
+const FILE_SALT_LEN: usize = 16;
+
+/// The environment variable to read the secret store file password from.
+const FILE_PASSWORD_ENV: &str = "FFSEND_SECRET_STORE_PASSWORD";
+
+/// Where to store owner tokens.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Backend {
+    /// Keep owner tokens in the history file.
+    History,
+
+    /// Store owner tokens in the system keyring.
+    Keyring,
+
+    /// Store owner tokens in an encrypted file.
+    File,
+
+    /// Use the system keyring if available, fall back to an encrypted file.
+    Auto,
+}
+
+impl Backend {
+    /// Get a list of all backend names.
+    pub fn names() -> &'static [&'static str] {
+        &["history", "keyring", "file", "auto"]
+    }
+
+    /// Select a backend by the given name, case insensitive.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "history" => Some(Backend::History),
+            "keyring" => Some(Backend::Keyring),
+            "file" => Some(Backend::File),
+            "auto" => Some(Backend::Auto),
+            _ => None,
+        }
+    }
+}
+
+/// An opened store for owner tokens.
+pub enum SecretStore {
+    /// The system keyring.
+    Keyring,
+
+    /// An encrypted file.
+    File(Box<FileStore>),
+}
+
+impl SecretStore {
+    /// Open the secret store selected by the user.
+    ///
+    /// `None` is returned if owner tokens are kept in the history file.
+    pub fn open(matcher_main: &MainMatcher) -> Result<Option<Self>, Error> {
+        Self::open_backend(matcher_main, true)
+    }
+
+    /// Open the secret store selected by the user, if it holds any tokens.
+    ///
+    /// Like `open`, but `None` is also returned if there is no store file yet, so reading tokens
+    /// doesn't prompt for the password of a new store.
+    pub fn open_existing(matcher_main: &MainMatcher) -> Result<Option<Self>, Error> {
+        Self::open_backend(matcher_main, false)
+    }
+
+    /// Open the secret store selected by the user, `create` specifies whether to create a new
+    /// store file if it doesn't exist.
+    fn open_backend(matcher_main: &MainMatcher, create: bool) -> Result<Option<Self>, Error> {
+        let backend = match matcher_main.secret_store() {
+            Backend::History => return Ok(None),
+            Backend::Auto if keyring_available() => Backend::Keyring,
+            Backend::Auto => Backend::File,
+            backend => backend,
+        };
+
+        Ok(Some(match backend {
+            Backend::Keyring => SecretStore::Keyring,
+            _ if !create && !FileStore::path().is_file() => return Ok(None),
+            _ => SecretStore::File(Box::new(FileStore::open(matcher_main)?)),
+        }))
+    }
+
+    /// Get the stored owner token for the given file, if any.
+    pub fn get(&self, file: &RemoteFile) -> Result<Option<String>, Error> {
+        match self {
+            SecretStore::Keyring => match keyring_entry(file)?.get_password() {
+                Ok(token) => Ok(Some(token)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(err) => Err(Error::Keyring(err)),
+            },
+            SecretStore::File(store) => Ok(store.tokens.get(&key(file)).cloned()),
+        }
+    }
+
+    /// Store the owner token for the given file.
+    pub fn set(&mut self, file: &RemoteFile, token: &str) -> Result<(), Error> {
+        match self {
+            SecretStore::Keyring => keyring_entry(file)?
+                .set_password(token)
+                .map_err(Error::Keyring),
+            SecretStore::File(store) => {
+                store.tokens.insert(key(file), token.into());
+                store.changed = true;
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove the stored owner token for the given file, if any.
+    pub fn remove(&mut self, file: &RemoteFile) -> Result<(), Error> {
+        match self {
+            SecretStore::Keyring => match keyring_entry(file)?.delete_password() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(err) => Err(Error::Keyring(err)),
+            },
+            SecretStore::File(store) => {
+                if store.tokens.remove(&key(file)).is_some() {
+                    store.changed = true;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Forget the owner tokens of all files not in the given list.
+    ///
+    /// This cleans up tokens of files removed from the history without accessing the store. The
+    /// keyring can't be listed, so only the store file is cleaned up.
+    pub fn retain(&mut self, files: &[RemoteFile]) {
+        if let SecretStore::File(store) = self {
+            let keys: HashSet<String> = files.iter().map(key).collect();
+            let len = store.tokens.len();
+            store.tokens.retain(|key, _| keys.contains(key));
+            if store.tokens.len() != len {
+                store.changed = true;
+            }
+        }
+    }
+
+    /// Save changes to the store.
+    ///
+    /// Changes to the keyring are saved immediately, this only writes the encrypted file.
+    pub fn save(&mut self) -> Result<(), Error> {
+        match self {
+            SecretStore::Keyring => Ok(()),
+            SecretStore::File(store) => store.save(),
+        }
+    }
+}
+
+/// The key to store the owner token of the given file by.
+fn key(file: &RemoteFile) -> String {
+    file.download_url(false).into()
+}
+
+/// Get the keyring entry for the owner token of the given file.
+fn keyring_entry(file: &RemoteFile) -> Result<keyring::Entry, Error> {
+    keyring::Entry::new(KEYRING_SERVICE, &key(file)).map_err(Error::Keyring)
+}
+
+/// Check whether the system keyring is available.
+fn keyring_available() -> bool {
+    match keyring::Entry::new(KEYRING_SERVICE, "availability-check") {
+        Ok(entry) => matches!(entry.get_password(), Ok(_) | Err(keyring::Error::NoEntry)),
+        Err(_) => false,
+    }
+}
+
+/// Owner tokens in an encrypted file.
+pub struct FileStore {
+    /// The path of the store file.
+    path: PathBuf,
+
+    /// The salt the key was derived with.
+    salt: Vec<u8>,
+
+    /// The key to encrypt the store with.
+    key: LessSafeKey,
+
+    /// The owner tokens, keyed by share URL.
+    tokens: HashMap<String, String>,
+
+    /// Whether the tokens have changed.
+    changed: bool,
+}
+
+impl FileStore {
+    /// The path of the store file.
+    fn path() -> PathBuf {
+        app_project_dirs().data_dir().join("owner-tokens.toml")
+    }
+
+    /// Open and decrypt the store file, a new store is created if it doesn't exist.
+    ///
+    /// The password is read from `FFSEND_SECRET_STORE_PASSWORD`, or prompted for.
+    fn open(matcher_main: &MainMatcher) -> Result<Self, Error> {
+        let path = Self::path();
+        let data = if path.is_file() {
+            let data = fs::read_to_string(&path).map_err(Error::Read)?;
+            Some(toml::from_str::<EncryptedFile>(&data).map_err(|_| Error::Malformed)?)
+        } else {
+            None
+        };
+        let password = file_password(matcher_main, data.is_none())?;
+
+        // Create a new empty store
+        let data = match data {
+            Some(data) => data,
+            None => {
+                let mut salt = vec![0; FILE_SALT_LEN];
+                SystemRandom::new()
+                    .fill(&mut salt)
+                    .map_err(|_| Error::Encrypt)?;
+                return Ok(Self {
+                    path,
+                    key: derive_key(&password, &salt)?,
+                    salt,
+                    tokens: HashMap::new(),
+                    changed: false,
+                });
+            }
+        };
+
+        // Decrypt the existing store
+        if data.version != FILE_VERSION {
+            return Err(Error::Malformed);
+        }
+        let salt = base64::decode(&data.salt).map_err(|_| Error::Malformed)?;
+        let nonce = base64::decode(&data.nonce).map_err(|_| Error::Malformed)?;
+        let nonce = Nonce::try_assume_unique_for_key(&nonce).map_err(|_| Error::Malformed)?;
+        let mut tokens = base64::decode(&data.tokens).map_err(|_| Error::Malformed)?;
+        let key = derive_key(&password, &salt)?;
+        let tokens = key
+            .open_in_place(nonce, Aad::empty(), &mut tokens)
+            .map_err(|_| Error::Decrypt)?;
+        let tokens = serde_json::from_slice(tokens).map_err(|_| Error::Malformed)?;
+
+        Ok(Self {
+            path,
+            salt,
+            key,
+            tokens,
+            changed: false,
+        })
+    }
+
+    /// Encrypt and save the store file, if changed.
+    fn save(&mut self) -> Result<(), Error> {
+        if !self.changed {
+            return Ok(());
+        }
+
+        // Encrypt the tokens with a fresh nonce
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| Error::Encrypt)?;
+        let mut tokens = serde_json::to_vec(&self.tokens).map_err(|_| Error::Encrypt)?;
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut tokens,
+            )
+            .map_err(|_| Error::Encrypt)?;
+        let data = toml::to_string(&EncryptedFile {
+            version: FILE_VERSION,
+            salt: base64::encode(&self.salt),
+            nonce: base64::encode(nonce),
+            tokens: base64::encode(&tokens),
+        })
+        .map_err(|_| Error::Encrypt)?;
+
+        // Write the file, only readable by the user
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(Error::Write)?;
+        }
+        #[cfg(unix)]
+        {
+            use std::fs::Permissions;
+            use std::os::unix::fs::PermissionsExt;
+
+            if !self.path.exists() {
+                fs::File::create(&self.path)
+                    .and_then(|file| file.set_permissions(Permissions::from_mode(0o600)))
+                    .map_err(Error::Write)?;
+            }
+        }
+        fs::write(&self.path, data).map_err(Error::Write)?;
+
+        self.changed = false;
+        Ok(())
+    }
+}
+
+/// The encrypted store file.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedFile {
+    /// The file format version.
+    version: u32,
+
+    /// The base64 encoded salt to derive the key with.
+    salt: String,
+
+    /// The base64 encoded nonce the tokens were encrypted with.
+    nonce: String,
+
+    /// The base64 encoded encrypted tokens.
+    tokens: String,
+}
+
+/// Get the password for the store file.
+///
+/// `new` specifies whether a new store is created, to phrase the prompt.
+fn file_password(matcher_main: &MainMatcher, new: bool) -> Result<String, Error> {
+    if let Ok(password) = env::var(FILE_PASSWORD_ENV) {
+        return Ok(password);
+    }
+    if matcher_main.no_interact() {
+        return Err(Error::NoPassword);
+    }
+
+    let prompt = if new {
+        "New secret store password: "
+    } else {
+        "Secret store password: "
+    };
+    let password = match prompt_password_stderr(prompt) {
+        Ok(password) if !password.is_empty() => password,
+        Ok(_) => return Err(Error::NoPassword),
+        Err(err) => return Err(Error::Prompt(err)),
+    };
+
+    // Confirm the password of a new store, a typo would lock away all owner tokens
+    if new {
+        match prompt_password_stderr("Confirm secret store password: ") {
+            Ok(confirm) if confirm == password => {}
+            Ok(_) => return Err(Error::PasswordMismatch),
+            Err(err) => return Err(Error::Prompt(err)),
+        }
+    }
+
+    Ok(password)
+}
+
+/// Derive the store file key from the given password and salt.
+fn derive_key(password: &str, salt: &[u8]) -> Result<LessSafeKey, Error> {
+    let mut key = [0; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        NonZeroU32::new(FILE_KDF_ITERATIONS).unwrap(),
+        salt,
+        password.as_bytes(),
+        &mut key,
+    );
+    UnboundKey::new(&AES_256_GCM, &key)
+        .map(LessSafeKey::new)
+        .map_err(|_| Error::Encrypt)
+}
+
+/// A secret store error.
+#[derive(Debug, Fail)]
+pub enum Error {
+    /// Failed to access the system keyring.
+    #[fail(display = "failed to access the system keyring")]
+    Keyring(#[cause] keyring::Error),
+
+    /// No password was given for the secret store file.
+    #[fail(display = "missing secret store password, must be set in no-interact mode")]
+    NoPassword,
+
+    /// The confirmed password for a new secret store file did not match.
+    #[fail(display = "secret store passwords don't match")]
+    PasswordMismatch,
+
+    /// Failed to prompt for the secret store file password.
+    #[fail(display = "failed to read secret store password from prompt")]
+    Prompt(#[cause] IoError),
+
+    /// Failed to read the secret store file.
+    #[fail(display = "failed to read the secret store file")]
+    Read(#[cause] IoError),
+
+    /// The secret store file is malformed.
+    #[fail(display = "the secret store file is malformed")]
+    Malformed,
+
+    /// Failed to decrypt the secret store file.
+    #[fail(display = "failed to decrypt the secret store file, wrong password?")]
+    Decrypt,
+
+    /// Failed to encrypt the secret store file.
+    #[fail(display = "failed to encrypt the secret store file")]
+    Encrypt,
+
+    /// Failed to write the secret store file.
+    #[fail(display = "failed to write the secret store file")]
+    Write(#[cause] IoError),
+}
@@ -3,26 +3,174 @@
 use ffsend_api::{
     api::request::{ensure_success, ResponseError},
     client::Client,
-    reqwest,
-    url::{self, Url},
+    reqwest::{self, header},
+    url::{self, form_urlencoded, Url},
 };
+use serde_json::Value;
 use urlshortener::{
     providers::{self, Provider},
-    request::{Method, Request},
+    request::{ContentType, Method, Request},
 };
 
 /// An URL shortening result.
 type Result<T> = ::std::result::Result<T, Error>;
 
-/// Shorten the given URL.
-pub fn shorten(client: &Client, url: &str) -> Result<String> {
-    // TODO: allow selecting other shorteners
-    request(client, providers::request(url, &Provider::IsGd))
+/// The name of the default shortener.
+pub const SHORTENER_DEFAULT: &str = "is.gd";
+
+/// The name of the generic shortener, using a custom URL template.
+pub const SHORTENER_TEMPLATE: &str = "template";
+
+/// The API endpoint of the biturl.top shortener.
+const BITURL_API: &str = "https://api.biturl.top/short";
+
+/// The placeholder in a template URL replaced with the URL to shorten.
+const TEMPLATE_URL: &str = "{url}";
+
+/// The placeholder in a template URL replaced with the API key.
+const TEMPLATE_KEY: &str = "{key}";
+
+/// A URL shortening service.
+#[derive(Clone, Debug)]
+pub enum Shortener {
+    /// A known public shortening provider.
+    Provider(Provider),
+
+    /// The biturl.top shortening provider, which the `urlshortener` crate doesn't support.
+    BitUrl,
+
+    /// A generic shortening service, such as a self-hosted YOURLS or Shlink instance.
+    ///
+    /// The URL is requested with a `GET` request, after replacing the `{url}` and `{key}`
+    /// placeholders in the template. The response body must be the plain shortened URL.
+    Template { url: String, key: Option<String> },
 }
 
-/// Shorten the given URL.
-pub fn shorten_url(client: &Client, url: &Url) -> Result<Url> {
-    Url::parse(&shorten(client, url.as_str())?).map_err(|err| err.into())
+impl Shortener {
+    /// Select a shortener by the given name.
+    ///
+    /// Some providers require an API `key`, the `url` may be used to specify a custom host for
+    /// self-hosted providers, and is the URL template for the generic template shortener.
+    pub fn from_name(
+        name: &str,
+        key: Option<String>,
+        url: Option<String>,
+    ) -> ::std::result::Result<Self, SelectError> {
+        let name = name.trim().to_lowercase();
+        let require_key = || key.clone().ok_or_else(|| SelectError::NoKey(name.clone()));
+
+        let provider = match name.as_str() {
+            "abv8" | "abv8.me" => Provider::Abv8,
+            "bambz" | "bam.bz" => Provider::BamBz,
+            "bitly" | "bit.ly" => Provider::BitLy {
+                token: require_key()?,
+            },
+            "biturl" | "biturl.top" => return Ok(Shortener::BitUrl),
+            "bmeo" | "bmeo.org" => Provider::Bmeo,
+            "fifocc" | "fifo.cc" => Provider::FifoCc,
+            "googl" | "goo.gl" => Provider::GooGl {
+                api_key: require_key()?,
+            },
+            "hecsu" | "hec.su" => Provider::HecSu,
+            "hmmrs" | "hmm.rs" => Provider::HmmRs,
+            "isgd" | "is.gd" => Provider::IsGd,
+            "kutt" | "kutt.it" => Provider::Kutt {
+                api_key: require_key()?,
+                host: url.map(|host| host.trim_end_matches('/').to_owned()),
+            },
+            "nowlinks" | "nowlinks.net" => Provider::NowLinks,
+            "phxcoin" | "phx.co.in" => Provider::PhxCoIn,
+            "psbeco" | "psbe.co" => Provider::PsbeCo,
+            "rlu" | "rlu.ru" => Provider::Rlu,
+            "scoop" | "s.coop" => Provider::SCoop,
+            "sirbz" | "sirbz.com" => Provider::SirBz,
+            "tinyph" | "tiny.ph" => Provider::TinyPh,
+            "tinyurl" | "tinyurl.com" => Provider::TinyUrl,
+            "tnyim" | "tny.im" => Provider::TnyIm,
+            "urlshortenerio" | "url-shortener.io" => Provider::UrlShortenerIo,
+            "vgd" | "v.gd" => Provider::VGd,
+            SHORTENER_TEMPLATE => {
+                let url = url.ok_or(SelectError::NoTemplate)?;
+                if !url.contains(TEMPLATE_URL) {
+                    return Err(SelectError::NoTemplate);
+                }
+                if url.contains(TEMPLATE_KEY) {
+                    require_key()?;
+                }
+                return Ok(Shortener::Template { url, key });
+            }
+            _ => return Err(SelectError::Unknown(name)),
+        };
+
+        Ok(Shortener::Provider(provider))
+    }
+
+    /// Get the shortener name as shown to the user.
+    pub fn name(&self) -> &str {
+        match self {
+            Shortener::Provider(provider) => provider.to_name(),
+            Shortener::BitUrl => "biturl.top",
+            Shortener::Template { .. } => SHORTENER_TEMPLATE,
+        }
+    }
+}
+
+/// Shorten the given URL with the given shortener.
+pub fn shorten(client: &Client, shortener: &Shortener, url: &str) -> Result<String> {
+    match shortener {
+        Shortener::Provider(provider) => {
+            let response = request(client, providers::request(url, provider))?;
+            providers::parse(&response, provider).map_err(|_| Error::Parse)
+        }
+        Shortener::BitUrl => {
+            let response = request(
+                client,
+                Request {
+                    url: BITURL_API.into(),
+                    body: Some(
+                        form_urlencoded::Serializer::new(String::new())
+                            .append_pair("url", url)
+                            .finish(),
+                    ),
+                    content_type: Some(ContentType::FormUrlEncoded),
+                    user_agent: None,
+                    headers: None,
+                    method: Method::Post,
+                },
+            )?;
+            serde_json::from_str::<Value>(&response)
+                .ok()
+                .and_then(|response| response.get("short")?.as_str().map(|url| url.to_owned()))
+                .filter(|url| !url.is_empty())
+                .ok_or(Error::Parse)
+        }
+        Shortener::Template { url: template, key } => {
+            let encode = |value: &str| -> String {
+                form_urlencoded::byte_serialize(value.as_bytes()).collect()
+            };
+            let target = template.replace(TEMPLATE_URL, &encode(url)).replace(
+                TEMPLATE_KEY,
+                &key.as_deref().map(encode).unwrap_or_default(),
+            );
+            let response = request(
+                client,
+                Request {
+                    url: target,
+                    body: None,
+                    content_type: None,
+                    user_agent: None,
+                    headers: None,
+                    method: Method::Get,
+                },
+            )?;
+            Ok(response.trim().to_owned())
+        }
+    }
+}
+
+/// Shorten the given URL with the given shortener.
+pub fn shorten_url(client: &Client, shortener: &Shortener, url: &Url) -> Result<Url> {
+    Url::parse(&shorten(client, shortener, url.as_str())?).map_err(|err| err.into())
 }
 
 /// Do the request as given, return the response.
@@ -33,27 +181,29 @@ fn request(client: &Client, req: Request) -> Result<String> {
         Method::Post => client.post(&req.url),
     };
 
+    // Define the custom headers
+    if let Some(headers) = req.headers {
+        builder = builder.headers(headers);
+    }
+
     // Define the custom user agent
-    if let Some(_agent) = req.user_agent.clone() {
-        // TODO: implement this
-        // builder.header(header::UserAgent::new(agent.0));
-        panic!("Custom UserAgent for URL shortener not yet implemented");
+    if let Some(agent) = req.user_agent {
+        builder = builder.header(header::USER_AGENT, agent.0);
     }
 
     // Define the custom content type
-    if let Some(_content_type) = req.content_type {
-        // TODO: implement this
-        // match content_type {
-        //     ContentType::Json => builder.header(header::ContentType::json()),
-        //     ContentType::FormUrlEncoded => {
-        //         builder.header(header::ContentType::form_url_encoded())
-        //     }
-        // };
-        panic!("Custom UserAgent for URL shortener not yet implemented");
+    if let Some(content_type) = req.content_type {
+        builder = builder.header(
+            header::CONTENT_TYPE,
+            match content_type {
+                ContentType::Json => "application/json",
+                ContentType::FormUrlEncoded => "application/x-www-form-urlencoded",
+            },
+        );
     }
 
     // Define the custom body
-    if let Some(body) = req.body.clone() {
+    if let Some(body) = req.body {
         builder = builder.body(body);
     }
 
@@ -80,6 +230,10 @@ pub enum Error {
     #[fail(display = "failed to shorten URL, got malformed response")]
     Malformed(#[cause] reqwest::Error),
 
+    /// The shortened URL could not be extracted from the server response.
+    #[fail(display = "failed to shorten URL, could not parse response")]
+    Parse,
+
     /// An error occurred while parsing the shortened URL.
     #[fail(display = "failed to shorten URL, could not parse URL")]
     Url(#[cause] url::ParseError),
@@ -96,3 +250,19 @@ impl From<ResponseError> for Error {
         Error::Response(err)
     }
 }
+
+/// An error selecting a URL shortener.
+#[derive(Debug, Fail)]
+pub enum SelectError {
+    /// The given shortener is unknown.
+    #[fail(display = "unknown URL shortener '{}'", _0)]
+    Unknown(String),
+
+    /// The shortener requires an API key, but none was given.
+    #[fail(display = "the '{}' URL shortener requires an API key", _0)]
+    NoKey(String),
+
+    /// The template shortener requires a URL template, but none was given.
+    #[fail(display = "the template URL shortener requires a URL template containing {{url}}")]
+    NoTemplate,
+}
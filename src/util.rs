@@ -9,6 +9,9 @@ use std::ffi::OsStr;
 #[cfg(feature = "clipboard")]
 use std::fmt;
 use std::fmt::{Debug, Display};
+#[cfg(all(feature = "clipboard", unix))]
+use std::fs::OpenOptions;
+use std::fs::{self, File};
 #[cfg(feature = "clipboard-bin")]
 use std::io::ErrorKind as IoErrorKind;
 use std::io::{self, Read};
@@ -17,14 +20,14 @@ use std::iter;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::exit;
-#[cfg(feature = "clipboard-bin")]
 use std::process::{Command, Stdio};
 
 #[cfg(feature = "clipboard-crate")]
 use self::clip::{ClipboardContext, ClipboardProvider};
 use chrono::Duration;
-use colored::*;
 #[cfg(feature = "history")]
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use colored::*;
 use directories::ProjectDirs;
 use failure::{err_msg, Fail};
 #[cfg(feature = "clipboard-crate")]
@@ -40,6 +43,7 @@ use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use regex::Regex;
 use rpassword::prompt_password_stderr;
+use sha2::{Digest, Sha256};
 #[cfg(feature = "clipboard-bin")]
 use which::which;
 
@@ -303,9 +307,21 @@ pub fn open_path(path: &str) -> Result<(), IoError> {
 }
 
 /// Set the clipboard of the user to the given `content` string.
+///
+/// The clipboard backend selected by the user is used. If clearing the clipboard is enabled, the
+/// clipboard is cleared in the background after the configured delay.
 #[cfg(feature = "clipboard")]
-pub fn set_clipboard(content: String) -> Result<(), ClipboardError> {
-    ClipboardType::select().set(content)
+pub fn set_clipboard(content: String, matcher_main: &MainMatcher) -> Result<(), ClipboardError> {
+    let clipboard = matcher_main.clipboard();
+    let hash = sha256(content.as_bytes());
+    clipboard.set(content)?;
+
+    // Schedule clearing the clipboard
+    if let Some(delay) = matcher_main.clipboard_clear() {
+        clipboard.clear_after(delay, &hash)?;
+    }
+
+    Ok(())
 }
 
 /// Clipboard management enum.
@@ -320,6 +336,9 @@ pub fn set_clipboard(content: String) -> Result<(), ClipboardError> {
 /// This limitation is due to security reasons as defined by X11. The alternative binaries we set
 /// the clipboard with spawn a daemon in the background to keep the clipboard alive until it's
 /// flushed.
+///
+/// In remote sessions, such as over SSH, the `Osc52` variant may be used to set the clipboard of
+/// the local machine through the terminal emulator.
 #[cfg(feature = "clipboard")]
 #[derive(Clone, Eq, PartialEq)]
 pub enum ClipboardType {
@@ -338,30 +357,111 @@ pub enum ClipboardType {
     /// May contain a binary path if specified at compile time through the `XSEL_PATH` variable.
     #[cfg(feature = "clipboard-bin")]
     Xsel(Option<String>),
+
+    /// Manage clipboard through `wl-copy` on Linux with Wayland.
+    ///
+    /// May contain a binary path if specified at compile time through the `WL_COPY_PATH`
+    /// variable.
+    #[cfg(feature = "clipboard-bin")]
+    WlCopy(Option<String>),
+
+    /// Set the clipboard through the OSC 52 terminal escape sequence.
+    ///
+    /// This is supported by many terminal emulators, and works in remote sessions.
+    Osc52,
 }
 
 #[cfg(feature = "clipboard")]
 impl ClipboardType {
+    /// Get a list of clipboard backend names that can be selected by the user.
+    pub fn names() -> Vec<&'static str> {
+        #[allow(unused_mut)]
+        let mut names = vec!["auto"];
+        #[cfg(feature = "clipboard-crate")]
+        names.push("native");
+        #[cfg(feature = "clipboard-bin")]
+        names.extend(&["xclip", "xsel", "wl-copy"]);
+        names.push("osc52");
+        names
+    }
+
+    /// Get the clipboard type for the given backend name.
+    ///
+    /// If `auto` is given, the best variant is selected through `ClipboardType::select()`.
+    /// `None` is returned if the name is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "auto" => Some(Self::select()),
+            #[cfg(feature = "clipboard-crate")]
+            "native" => Some(ClipboardType::Native),
+            #[cfg(feature = "clipboard-bin")]
+            "xclip" => Some(ClipboardType::Xclip(
+                option_env!("XCLIP_PATH").map(|p| p.to_owned()),
+            )),
+            #[cfg(feature = "clipboard-bin")]
+            "xsel" => Some(ClipboardType::Xsel(
+                option_env!("XSEL_PATH").map(|p| p.to_owned()),
+            )),
+            #[cfg(feature = "clipboard-bin")]
+            "wl-copy" | "wayland" => Some(ClipboardType::WlCopy(
+                option_env!("WL_COPY_PATH").map(|p| p.to_owned()),
+            )),
+            "osc52" | "osc-52" | "terminal" => Some(ClipboardType::Osc52),
+            _ => None,
+        }
+    }
+
+    /// Get the backend name of this clipboard type, as accepted by `ClipboardType::from_name()`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "clipboard-crate")]
+            ClipboardType::Native => "native",
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::Xclip(_) => "xclip",
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::Xsel(_) => "xsel",
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::WlCopy(_) => "wl-copy",
+            ClipboardType::Osc52 => "osc52",
+        }
+    }
+
     /// Select the clipboard type to use, depending on the runtime system.
     pub fn select() -> Self {
         #[cfg(feature = "clipboard-crate")]
         {
-            ClipboardType::Native
+            // Use the terminal in remote sessions, the native clipboard is on the remote machine
+            if is_remote_session() {
+                ClipboardType::Osc52
+            } else {
+                ClipboardType::Native
+            }
         }
 
         #[cfg(feature = "clipboard-bin")]
         {
+            let wayland = var_os("WAYLAND_DISPLAY").is_some();
+            let x11 = var_os("DISPLAY").is_some();
+
             if let Some(path) = option_env!("XCLIP_PATH") {
                 ClipboardType::Xclip(Some(path.to_owned()))
             } else if let Some(path) = option_env!("XSEL_PATH") {
                 ClipboardType::Xsel(Some(path.to_owned()))
+            } else if let Some(path) = option_env!("WL_COPY_PATH") {
+                ClipboardType::WlCopy(Some(path.to_owned()))
+            } else if wayland && which("wl-copy").is_ok() {
+                ClipboardType::WlCopy(None)
+            } else if !x11 && !wayland && is_remote_session() {
+                ClipboardType::Osc52
             } else if which("xclip").is_ok() {
                 ClipboardType::Xclip(None)
             } else if which("xsel").is_ok() {
                 ClipboardType::Xsel(None)
+            } else if which("wl-copy").is_ok() {
+                ClipboardType::WlCopy(None)
             } else {
-                // TODO: should we error here instead, as no clipboard binary was found?
-                ClipboardType::Xclip(None)
+                // No clipboard binary available, fall back to the terminal
+                ClipboardType::Osc52
             }
         }
     }
@@ -375,9 +475,93 @@ impl ClipboardType {
             ClipboardType::Xclip(path) => Self::xclip_set(path.clone(), &content),
             #[cfg(feature = "clipboard-bin")]
             ClipboardType::Xsel(path) => Self::xsel_set(path.clone(), &content),
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::WlCopy(path) => Self::wl_copy_set(path.clone(), &content),
+            ClipboardType::Osc52 => Self::osc52_set(&content),
         }
     }
 
+    /// Get the clipboard contents through the selected clipboard type.
+    pub fn get(&self) -> Result<String, ClipboardError> {
+        match self {
+            #[cfg(feature = "clipboard-crate")]
+            ClipboardType::Native => Self::native_get(),
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::Xclip(path) => Self::sys_cmd_get(
+                "xclip",
+                Command::new(path.clone().unwrap_or_else(|| "xclip".into()))
+                    .arg("-sel")
+                    .arg("clip")
+                    .arg("-o"),
+            ),
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::Xsel(path) => Self::sys_cmd_get(
+                "xsel",
+                Command::new(path.clone().unwrap_or_else(|| "xsel".into()))
+                    .arg("--clipboard")
+                    .arg("--output"),
+            ),
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::WlCopy(_) => Self::sys_cmd_get(
+                "wl-paste",
+                Command::new(option_env!("WL_PASTE_PATH").unwrap_or("wl-paste"))
+                    .arg("--no-newline"),
+            ),
+            ClipboardType::Osc52 => Err(ClipboardError::NoRead(self.name())),
+        }
+    }
+
+    /// Clear the clipboard contents.
+    pub fn clear(&self) -> Result<(), ClipboardError> {
+        match self {
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::Xsel(path) => Self::sys_cmd_set(
+                "xsel",
+                Command::new(path.clone().unwrap_or_else(|| "xsel".into()))
+                    .arg("--clipboard")
+                    .arg("--clear"),
+                "",
+            ),
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::WlCopy(path) => Self::sys_cmd_set(
+                "wl-copy",
+                Command::new(path.clone().unwrap_or_else(|| "wl-copy".into())).arg("--clear"),
+                "",
+            ),
+            _ => self.set(String::new()),
+        }
+    }
+
+    /// Clear the clipboard contents in the background, after the given delay in seconds.
+    ///
+    /// This spawns a detached process of this application to clear the clipboard, so it's
+    /// cleared even though this application quits before the delay passes. The clipboard is only
+    /// cleared if it still holds the content with the given SHA-256 `hash`, so content copied
+    /// in the meantime is kept. The hash is passed through the standard input of the process
+    /// rather than as argument, to not expose it in the process list.
+    pub fn clear_after(&self, delay: u64, hash: &str) -> Result<(), ClipboardError> {
+        let exe = current_exe().map_err(ClipboardError::Clear)?;
+        let mut child = Command::new(exe)
+            .arg("--clipboard-backend")
+            .arg(self.name())
+            .arg("clear-clipboard")
+            .arg("--delay")
+            .arg(delay.to_string())
+            .arg("--content-hash-stdin")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(ClipboardError::Clear)?;
+
+        // Write the hash and close the pipe, the process is left running in the background
+        child
+            .stdin
+            .take()
+            .expect("clear clipboard process has no stdin")
+            .write_all(format!("{}\n", hash).as_bytes())
+            .map_err(ClipboardError::Clear)
+    }
+
     /// Set the clipboard through a native interface.
     ///
     /// This is used on non-Linux systems.
@@ -389,6 +573,17 @@ impl ClipboardType {
             .map_err(ClipboardError::Native)
     }
 
+    /// Get the clipboard contents through a native interface.
+    ///
+    /// This is used on non-Linux systems.
+    #[cfg(feature = "clipboard-crate")]
+    fn native_get() -> Result<String, ClipboardError> {
+        ClipboardProvider::new()
+            .and_then(|mut context: ClipboardContext| context.get_contents())
+            .map_err(|err| format_err!("{}", err).compat())
+            .map_err(ClipboardError::Native)
+    }
+
     #[cfg(feature = "clipboard-bin")]
     fn xclip_set(path: Option<String>, content: &str) -> Result<(), ClipboardError> {
         Self::sys_cmd_set(
@@ -409,6 +604,38 @@ impl ClipboardType {
         )
     }
 
+    #[cfg(feature = "clipboard-bin")]
+    fn wl_copy_set(path: Option<String>, content: &str) -> Result<(), ClipboardError> {
+        Self::sys_cmd_set(
+            "wl-copy",
+            &mut Command::new(path.unwrap_or_else(|| "wl-copy".into())),
+            content,
+        )
+    }
+
+    /// Set the clipboard by writing the OSC 52 escape sequence to the terminal.
+    ///
+    /// The sequence is wrapped in a passthrough sequence when running inside tmux.
+    fn osc52_set(content: &str) -> Result<(), ClipboardError> {
+        let mut sequence = format!("\x1b]52;c;{}\x07", base64::encode(content));
+        if var_os("TMUX").is_some() {
+            sequence = format!("\x1bPtmux;\x1b{}\x1b\\", sequence);
+        }
+
+        // Prefer writing to the controlling terminal, fall back to stderr
+        #[cfg(unix)]
+        {
+            if let Ok(mut tty) = OpenOptions::new().write(true).open("/dev/tty") {
+                return tty
+                    .write_all(sequence.as_bytes())
+                    .map_err(ClipboardError::Terminal);
+            }
+        }
+        stderr()
+            .write_all(sequence.as_bytes())
+            .map_err(ClipboardError::Terminal)
+    }
+
     #[cfg(feature = "clipboard-bin")]
     fn sys_cmd_set(
         bin: &'static str,
@@ -447,6 +674,28 @@ impl ClipboardType {
 
         Ok(())
     }
+
+    #[cfg(feature = "clipboard-bin")]
+    fn sys_cmd_get(bin: &'static str, command: &mut Command) -> Result<String, ClipboardError> {
+        // Run the command process for getting the clipboard
+        let output = match command.stdin(Stdio::null()).output() {
+            Ok(output) => output,
+            Err(err) => {
+                return Err(match err.kind() {
+                    IoErrorKind::NotFound => ClipboardError::NoBinary,
+                    _ => ClipboardError::BinaryIo(bin, err),
+                });
+            }
+        };
+        if !output.status.success() {
+            return Err(ClipboardError::BinaryStatus(
+                bin,
+                output.status.code().unwrap_or(0),
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
 }
 
 #[cfg(feature = "clipboard")]
@@ -465,10 +714,22 @@ impl fmt::Display for ClipboardType {
                 None => write!(f, "xsel"),
                 Some(path) => write!(f, "xsel ({})", path),
             },
+            #[cfg(feature = "clipboard-bin")]
+            ClipboardType::WlCopy(path) => match path {
+                None => write!(f, "wl-copy"),
+                Some(path) => write!(f, "wl-copy ({})", path),
+            },
+            ClipboardType::Osc52 => write!(f, "OSC 52 terminal escape"),
         }
     }
 }
 
+/// Check whether we're running in a remote session, such as over SSH.
+#[cfg(feature = "clipboard")]
+fn is_remote_session() -> bool {
+    var_os("SSH_TTY").is_some() || var_os("SSH_CONNECTION").is_some()
+}
+
 #[cfg(feature = "clipboard")]
 #[derive(Debug, Fail)]
 pub enum ClipboardError {
@@ -479,25 +740,41 @@ pub enum ClipboardError {
     #[fail(display = "failed to access clipboard")]
     Native(#[cause] Compat<Error>),
 
-    /// The `xclip` or `xsel` binary could not be found on the system, required for clipboard support.
+    /// The `xclip`, `xsel` or `wl-copy` binary could not be found on the system, required for
+    /// clipboard support.
     #[cfg(feature = "clipboard-bin")]
-    #[fail(display = "failed to access clipboard, xclip or xsel is not installed")]
+    #[fail(display = "failed to access clipboard, xclip, xsel or wl-copy is not installed")]
     NoBinary,
 
-    /// An error occurred while using `xclip` or `xsel` to set the clipboard contents.
+    /// An error occurred while using `xclip`, `xsel` or `wl-copy` to set the clipboard contents.
     /// This problem probably occurred when starting, or while piping the clipboard contents to
     /// the process.
     #[cfg(feature = "clipboard-bin")]
     #[fail(display = "failed to access clipboard using {}", _0)]
     BinaryIo(&'static str, #[cause] IoError),
 
-    /// `xclip` or `xsel` unexpectedly exited with a non-successful status code.
+    /// `xclip`, `xsel` or `wl-copy` unexpectedly exited with a non-successful status code.
     #[cfg(feature = "clipboard-bin")]
     #[fail(
         display = "failed to use clipboard, {} exited with status code {}",
         _0, _1
     )]
     BinaryStatus(&'static str, i32),
+
+    /// The selected clipboard backend does not support reading the clipboard.
+    #[fail(
+        display = "reading the clipboard is not supported with the {} backend",
+        _0
+    )]
+    NoRead(&'static str),
+
+    /// Failed to write the OSC 52 escape sequence to the terminal.
+    #[fail(display = "failed to set clipboard through terminal")]
+    Terminal(#[cause] IoError),
+
+    /// Failed to spawn the background process to clear the clipboard.
+    #[fail(display = "failed to schedule clearing the clipboard")]
+    Clear(#[cause] IoError),
 }
 
 /// Check for an empty password in the given `password`.
@@ -517,10 +794,116 @@ pub fn check_empty_password(password: &str, matcher_main: &MainMatcher) {
     }
 }
 
+/// A source to read passwords from, instead of prompting the user.
+#[derive(Debug, Clone)]
+pub enum PasswordSource {
+    /// Read from a file, or from stdin if `-`.
+    File(PathBuf),
+
+    /// Read from a file descriptor.
+    #[cfg(unix)]
+    Fd(i32),
+
+    /// Read from the output of a shell command, such as a password manager.
+    Cmd(String),
+}
+
+impl PasswordSource {
+    /// Read the password from this source.
+    ///
+    /// Only the first line is used, as password managers may output additional details on
+    /// following lines.
+    pub fn read(&self) -> Result<String, PasswordSourceError> {
+        let data = match self {
+            PasswordSource::File(path) if path == Path::new("-") => {
+                let mut data = String::new();
+                stdin()
+                    .read_to_string(&mut data)
+                    .map_err(PasswordSourceError::File)?;
+                data
+            }
+            PasswordSource::File(path) => {
+                fs::read_to_string(path).map_err(PasswordSourceError::File)?
+            }
+            #[cfg(unix)]
+            PasswordSource::Fd(fd) => {
+                use std::os::unix::io::FromRawFd;
+
+                // The file descriptor is given by the user for us to read, and closed once read
+                let mut file = unsafe { File::from_raw_fd(*fd) };
+                let mut data = String::new();
+                file.read_to_string(&mut data)
+                    .map_err(PasswordSourceError::Fd)?;
+                data
+            }
+            PasswordSource::Cmd(cmd) => {
+                #[cfg(not(windows))]
+                let mut command = Command::new("sh");
+                #[cfg(not(windows))]
+                command.arg("-c").arg(cmd);
+                #[cfg(windows)]
+                let mut command = Command::new("cmd");
+                #[cfg(windows)]
+                command.arg("/C").arg(cmd);
+
+                let output = command
+                    .stderr(Stdio::inherit())
+                    .output()
+                    .map_err(PasswordSourceError::Cmd)?;
+                if !output.status.success() {
+                    return Err(PasswordSourceError::CmdStatus(
+                        output.status.code().unwrap_or(0),
+                    ));
+                }
+                String::from_utf8(output.stdout).map_err(|_| PasswordSourceError::Utf8)?
+            }
+        };
+
+        Ok(data.lines().next().unwrap_or("").into())
+    }
+}
+
+/// Read a password from the password source the user configured, if any.
+///
+/// If reading the password failed, the program will quit with an error message.
+pub fn read_password_source(main_matcher: &MainMatcher) -> Option<String> {
+    let source = main_matcher.password_source()?;
+    match source.read() {
+        Ok(password) => Some(password),
+        Err(err) => quit_error(
+            err.context("failed to obtain password"),
+            ErrorHintsBuilder::default()
+                .password(true)
+                .verbose(false)
+                .build()
+                .unwrap(),
+        ),
+    }
+}
+
 /// Prompt the user to enter a password.
 ///
+/// If a password source is configured, the password is read from it instead.
 /// If `empty` is `false`, empty passwords aren't allowed unless forced.
 pub fn prompt_password(main_matcher: &MainMatcher, optional: bool) -> Option<String> {
+    // Read the password from the configured source instead of prompting
+    if let Some(password) = read_password_source(main_matcher) {
+        if !password.is_empty() {
+            return Some(password);
+        }
+        if optional {
+            return None;
+        }
+        quit_error_msg(
+            "the password source did not provide a password",
+            ErrorHintsBuilder::default()
+                .password(true)
+                .verbose(false)
+                .build()
+                .unwrap(),
+        );
+    }
+
     // Quit with an error if we may not interact
     if !optional && main_matcher.no_interact() {
         quit_error_msg(
@@ -598,8 +981,12 @@ pub fn ensure_password(
     loop {
         // Prompt for an owner token if not set yet
         if password.is_none() {
-            // Do not ask for a token if optional when non-interactive or forced
-            if optional && (!interact || main_matcher.force()) {
+            // Do not ask for a token if optional when non-interactive or forced, unless a
+            // password source is explicitly given
+            if optional
+                && (!interact || main_matcher.force())
+                && !main_matcher.password_source_given()
+            {
                 return false;
             }
 
@@ -812,6 +1199,34 @@ pub fn ensure_owner_token(
     }
 }
 
+/// Compute the SHA-256 checksum of the file at the given path.
+///
+/// The checksum is returned as lowercase hexadecimal string.
+pub fn sha256_file(path: &Path) -> Result<String, IoError> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hex_string(&hasher.finalize()))
+}
+
+/// Compute the SHA-256 checksum of the given data.
+///
+/// The checksum is returned as lowercase hexadecimal string.
+#[cfg(feature = "clipboard")]
+pub fn sha256(data: &[u8]) -> String {
+    hex_string(&Sha256::digest(data))
+}
+
+/// Format the given bytes as lowercase hexadecimal string.
+pub fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Check whether the given string is a valid SHA-256 checksum in hexadecimal notation.
+pub fn is_sha256_hex(checksum: &str) -> bool {
+    checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 /// Format the given number of bytes readable for humans.
 pub fn format_bytes(bytes: u64) -> String {
     let bytes = bytes as f64;
@@ -825,6 +1240,68 @@ pub fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// Parse the given size string from human readable format into bytes.
+///
+/// Binary units are used, and the `B` and `iB` suffixes are optional.
+/// The following size strings can be parsed:
+/// - `1024`
+/// - `500M`
+/// - `1.5GiB`
+/// - `2 GB`
+pub fn parse_bytes(size: &str) -> Result<u64, ParseBytesError> {
+    // Build a regex to grab the value and unit
+    let re = Regex::new(r"(?i)^\s*([0-9]+(?:\.[0-9]+)?)\s*([kmgt]?)(?:i?b)?\s*$")
+        .expect("failed to compile size parsing regex");
+    let capture = re.captures(size).ok_or(ParseBytesError::Invalid)?;
+
+    // Parse the value, multiply by unit
+    let value = capture[1]
+        .parse::<f64>()
+        .map_err(|_| ParseBytesError::Invalid)?;
+    let exp = match capture[2].to_lowercase().as_str() {
+        "" => 0,
+        "k" => 1,
+        "m" => 2,
+        "g" => 3,
+        "t" => 4,
+        _ => return Err(ParseBytesError::Invalid),
+    };
+    Ok((value * 1024f64.powi(exp)) as u64)
+}
+
+/// A password source error.
+#[derive(Debug, Fail)]
+pub enum PasswordSourceError {
+    /// Failed to read the password file.
+    #[fail(display = "failed to read password file")]
+    File(#[cause] IoError),
+
+    /// Failed to read from the password file descriptor.
+    #[cfg(unix)]
+    #[fail(display = "failed to read password from file descriptor")]
+    Fd(#[cause] IoError),
+
+    /// Failed to run the password command.
+    #[fail(display = "failed to run password command")]
+    Cmd(#[cause] IoError),
+
+    /// The password command exited with a non-zero status code.
+    #[fail(display = "password command exited with status code {}", _0)]
+    CmdStatus(i32),
+
+    /// The password command output is not valid UTF-8.
+    #[fail(display = "password command output is not valid UTF-8")]
+    Utf8,
+}
+
+/// Represents a size parsing error.
+#[derive(Debug, Fail)]
+pub enum ParseBytesError {
+    /// The given size string is not a valid size.
+    #[fail(display = "invalid size, use a number with an optional K, M, G or T unit")]
+    Invalid,
+}
+
 /// Parse the given duration string from human readable format into seconds.
 /// This method parses a string of time components to represent the given duration.
 ///
@@ -888,6 +1365,62 @@ pub enum ParseDurationError {
     UnknownIdentifier(String),
 }
 
+/// Parse the given point in time from human readable format.
+///
+/// The following time strings can be parsed:
+/// - `2026-10-20 17:00`: local date and time, seconds are optional
+/// - `2026-10-20`: the start of a local date
+/// - `2026-10-20T17:00:00+02:00`: RFC 3339 time
+/// - `3d12h`: a duration from now, see `parse_duration`
+#[cfg(feature = "history")]
+pub fn parse_datetime(time: &str) -> Result<DateTime<Utc>, ParseDateTimeError> {
+    let time = time.trim();
+
+    // Parse an RFC 3339 time
+    if let Ok(time) = DateTime::parse_from_rfc3339(time) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    // Parse a local date and time, or just a date
+    let local = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(time, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(time, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+    });
+    if let Some(local) = local {
+        return Local
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|time| time.with_timezone(&Utc))
+            .ok_or(ParseDateTimeError::Invalid);
+    }
+
+    // Parse a duration from now, which only has numbers and time identifiers
+    let duration = time.trim_start_matches('+');
+    if !duration.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(ParseDateTimeError::Invalid);
+    }
+    let secs = parse_duration(duration).map_err(|_| ParseDateTimeError::Invalid)?;
+    Ok(Utc::now() + Duration::seconds(secs as i64))
+}
+
+/// Represents a point in time parsing error.
+#[cfg(feature = "history")]
+#[derive(Debug, Fail)]
+pub enum ParseDateTimeError {
+    /// The given time string is not a valid time.
+    #[fail(display = "invalid time, use a date and time such as '2026-10-20 17:00' or a duration")]
+    Invalid,
+}
+
 /// Format the given duration in a human readable format.
 /// This method builds a string of time components to represent
 /// the given duration.
@@ -1012,7 +1545,6 @@ pub fn ensure_enough_space<P: AsRef<Path>>(path: P, size: u64) {
 /// Get the project directories instance for this application.
 /// This may be used to determine the project, cache, configuration, data and
 /// some other directory paths.
-#[cfg(feature = "history")]
 pub fn app_project_dirs() -> ProjectDirs {
     ProjectDirs::from("", "", crate_name!())
         .expect("failed to determine location of project directories")